@@ -0,0 +1,386 @@
+// in-memory cache of the responses retrieved by CCNYCourseCatalog
+// repeated lookups within the same session are served from here instead of re-downloading the department
+use crate::api::disk_cache::DiskCache;
+use crate::data_structures::concurrent_cache::ConcurrentLruCache;
+use crate::data_structures::custom_lru_cache::{Clock, CustomLruCache, SystemClock};
+use crate::models::CourseInfo;
+use serde::Serialize;
+use std::future::Future;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Configuration of the in-memory response cache used by [`crate::CCNYCourseCatalog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// maximum number of entries kept per kind of response (department listings, course details)
+    pub capacity : usize,
+
+    /// how long a cached response remains valid
+    pub ttl : Duration,
+
+    /// optional bound on the approximate size (in bytes) of the responses kept per kind of response,
+    /// so that a few large department listings don't take up as much memory as `capacity` of them would
+    pub max_bytes : Option<usize>,
+
+    /// enables stale-while-revalidate for department listings : once a listing is older than `ttl`,
+    /// it keeps being served for up to this much longer while a fresh copy is downloaded in the background,
+    /// past that point the next lookup waits for the download instead
+    pub max_stale : Option<Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            capacity : 64,
+            ttl : Duration::from_secs(60 * 60),
+            max_bytes : None,
+            max_stale : None,
+        }
+    }
+}
+
+// the cache is only split into shards once each shard can hold at least this many entries
+// so that small caches still evict their least recently used entry
+const ENTRIES_PER_SHARD : usize = 16;
+const MAX_SHARD_COUNT : usize = 8;
+
+// a cached response and the time it was cached at
+#[derive(Debug, Clone)]
+struct CachedResponse<V> {
+    value : V,
+    cached_at : Instant,
+
+    // how old the response already was once cached, i.e. when it's read back from the disk cache
+    // so that it's ttl doesn't restart
+    age_when_cached : Duration,
+
+    // approximate size of the response, only computed if the cache is bounded by size (0 otherwise)
+    // weighing a department listing means going through all of it's courses,
+    // so it's done before the shard is locked and the shard's weigher simply reads it
+    weight : usize,
+}
+
+// result of looking up a response, stale responses are older than the ttl but still within max_stale
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheLookup<V> {
+    Fresh(V),
+    Stale(V),
+    Missing,
+}
+
+// lru cache of responses that expire once they are older than the configured ttl (plus max_stale)
+// clones share the same entries, so it can be used by concurrent tasks without an outer lock
+#[derive(Debug, Clone)]
+pub struct ResponseCache<V> {
+    entries : ConcurrentLruCache<String, CachedResponse<V>>,
+    ttl : Duration,
+
+    // ttl plus max_stale, responses older than this are never served
+    lifetime : Duration,
+
+    // whether the entries are bounded by their approximate size (i.e. max_bytes was set)
+    weighed : bool,
+    clock : Arc<dyn Clock>,
+}
+
+impl<V : Clone + Serialize + 'static> ResponseCache<V> {
+    pub fn new(config : CacheConfig) -> Self {
+        ResponseCache::with_clock(config, Arc::new(SystemClock))
+    }
+
+    // same as new, reading the current time from the provided clock
+    pub fn with_clock(config : CacheConfig, clock : Arc<dyn Clock>) -> Self {
+        let shard_count = (config.capacity / ENTRIES_PER_SHARD).clamp(1, MAX_SHARD_COUNT);
+        let shard_capacity = config.capacity.div_ceil(shard_count);
+
+        // stale responses have to outlive the ttl, so the entries are only dropped once they are too stale to be served
+        let lifetime = config.ttl.saturating_add(config.max_stale.unwrap_or(Duration::ZERO));
+        let entries = ConcurrentLruCache::with_shards(shard_count, || {
+            let mut shard = CustomLruCache::with_ttl_and_clock(shard_capacity, Some(lifetime), Arc::clone(&clock));
+            if let Some(max_bytes) = config.max_bytes {
+                shard.set_weigher(max_bytes / shard_count, |_ : &String, entry : &CachedResponse<V>| entry.weight);
+            }
+            shard
+        });
+
+        ResponseCache {
+            entries,
+            ttl : config.ttl,
+            lifetime,
+            weighed : config.max_bytes.is_some(),
+            clock,
+        }
+    }
+
+    // returns a copy of the cached value if it's still fresh
+    pub fn get(&self, key : &str) -> Option<V> {
+        match self.lookup(key) {
+            CacheLookup::Fresh(value) => Some(value),
+            CacheLookup::Stale(_) | CacheLookup::Missing => None,
+        }
+    }
+
+    // returns a copy of the cached value, telling apart fresh and stale values
+    // values that are too stale to be served are dropped by the underlying cache
+    // (or here, for values that were already old once cached)
+    pub fn lookup(&self, key : &str) -> CacheLookup<V> {
+        let key = key.to_owned();
+        let Some(entry) = self.entries.get(&key) else {
+            return CacheLookup::Missing;
+        };
+
+        let age = self.clock.now().saturating_duration_since(entry.cached_at).saturating_add(entry.age_when_cached);
+        if age < self.ttl {
+            CacheLookup::Fresh(entry.value)
+        } else if age < self.lifetime {
+            CacheLookup::Stale(entry.value)
+        } else {
+            self.entries.remove(&key);
+            CacheLookup::Missing
+        }
+    }
+
+    // caches a response that was just downloaded
+    pub fn insert(&self, key : &str, value : V) {
+        self.insert_with_age(key, value, Duration::ZERO);
+    }
+
+    // caches a response that was downloaded age ago (i.e. read from the disk cache)
+    pub fn insert_with_age(&self, key : &str, value : V, age : Duration) {
+        let entry = CachedResponse {
+            weight : if self.weighed { approximate_size(key, &value) } else { 0 },
+            value,
+            cached_at : self.clock.now(),
+            age_when_cached : age,
+        };
+        self.entries.insert(key.to_owned(), entry);
+    }
+
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+}
+
+impl<V : Clone + Serialize + Send + Sync + 'static> ResponseCache<V> {
+
+    // returns a copy of the cached value if it's still fresh, otherwise awaits the loader and caches it's result
+    // the loader returns the value along with it's age (refer to insert_with_age)
+    // concurrent calls for the same key share a single load (i.e. a single download)
+    pub async fn get_or_load<F, Fut>(&self, key : &str, loader : F) -> anyhow::Result<V>
+    where
+        F : FnOnce() -> Fut,
+        Fut : Future<Output = anyhow::Result<(V, Duration)>> + Send + 'static,
+    {
+        match self.get(key) {
+            Some(value) => Ok(value),
+            None => self.load(key, loader).await,
+        }
+    }
+
+    // awaits the loader and caches it's result, replacing the cached value (i.e. a stale one)
+    // concurrent calls for the same key share a single load, like get_or_load
+    pub async fn load<F, Fut>(&self, key : &str, loader : F) -> anyhow::Result<V>
+    where
+        F : FnOnce() -> Fut,
+        Fut : Future<Output = anyhow::Result<(V, Duration)>> + Send + 'static,
+    {
+        let clock = Arc::clone(&self.clock);
+        let weighed = self.weighed;
+        let owned_key = key.to_owned();
+        let entry = self.entries.load_with(key.to_owned(), move || {
+            let load = loader();
+            async move {
+                let (value, age) = load.await?;
+                Ok(CachedResponse {
+                    weight : if weighed { approximate_size(&owned_key, &value) } else { 0 },
+                    value,
+                    cached_at : clock.now(),
+                    age_when_cached : age,
+                })
+            }
+        }).await?;
+
+        Ok(entry.value)
+    }
+}
+
+// size of the key and of the value's json representation, close enough to the memory used by the response
+// the json is only counted, not kept, so weighing doesn't allocate a copy of the response
+fn approximate_size<V : Serialize>(key : &str, value : &V) -> usize {
+    let mut counter = ByteCounter(0);
+    match serde_json::to_writer(&mut counter, value) {
+        Ok(()) => key.len() + counter.0,
+        Err(_) => key.len(),
+    }
+}
+
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, bytes : &[u8]) -> std::io::Result<usize> {
+        self.0 += bytes.len();
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// the responses cached by a single catalog instance
+// department listings are keyed by department id (i.e. "CSC-CTY")
+// course details are keyed by course group id (i.e. "0455351")
+// cloning shares the cached responses
+#[derive(Debug, Clone)]
+pub struct CatalogCache {
+    pub departments : ResponseCache<Vec<CourseInfo>>,
+    pub course_details : ResponseCache<serde_json::Value>,
+
+    // consulted when a response isn't in memory, before downloading it
+    pub disk : Option<DiskCache>,
+}
+
+impl CatalogCache {
+    pub fn new(config : CacheConfig) -> Self {
+        CatalogCache {
+            departments : ResponseCache::new(config),
+            course_details : ResponseCache::new(config),
+            disk : None,
+        }
+    }
+
+    // only clears the in-memory responses, the disk cache is cleared through DiskCache::clear
+    pub fn clear(&self) {
+        self.departments.clear();
+        self.course_details.clear();
+    }
+}
+
+impl Default for CatalogCache {
+    fn default() -> Self {
+        CatalogCache::new(CacheConfig::default())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::custom_lru_cache::ManualClock;
+
+    fn config(capacity : usize, ttl : Duration) -> CacheConfig {
+        CacheConfig { capacity, ttl, max_bytes : None, max_stale : None }
+    }
+
+    #[test]
+    fn test_cached_values_are_returned() {
+        let cache : ResponseCache<String> = ResponseCache::new(config(2, Duration::from_secs(60)));
+        cache.insert("CSC-CTY", String::from("computer science"));
+        assert_eq!(cache.get("CSC-CTY"), Some(String::from("computer science")));
+        assert_eq!(cache.get("EE-CTY"), None);
+    }
+
+    #[test]
+    fn test_least_recently_used_is_evicted() {
+        let cache : ResponseCache<String> = ResponseCache::new(config(2, Duration::from_secs(60)));
+        cache.insert("a", String::from("a"));
+        cache.insert("b", String::from("b"));
+        cache.get("a");
+        cache.insert("c", String::from("c"));
+
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(String::from("a")));
+        assert_eq!(cache.get("c"), Some(String::from("c")));
+    }
+
+    #[test]
+    fn test_values_never_expire_with_the_maximum_ttl() {
+        let cache_config = CacheConfig { max_stale : Some(Duration::from_secs(30)), ..config(2, Duration::MAX) };
+        let cache : ResponseCache<String> = ResponseCache::new(cache_config);
+        cache.insert("CSC-CTY", String::from("computer science"));
+        assert_eq!(cache.get("CSC-CTY"), Some(String::from("computer science")));
+    }
+
+    #[test]
+    fn test_expired_values_are_dropped() {
+        let cache : ResponseCache<String> = ResponseCache::new(config(2, Duration::ZERO));
+        cache.insert("a", String::from("a"));
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn test_large_responses_are_bounded_by_size() {
+        let cache : ResponseCache<String> = ResponseCache::new(CacheConfig { max_bytes : Some(64), ..config(16, Duration::from_secs(60)) });
+        cache.insert("small", String::from("a"));
+        cache.insert("large", "b".repeat(40));
+        assert_eq!(cache.get("small"), Some(String::from("a")));
+
+        // the second large response pushes out the least recently used one
+        cache.insert("larger", "c".repeat(40));
+        assert_eq!(cache.get("large"), None);
+        assert_eq!(cache.get("small"), Some(String::from("a")));
+        assert!(cache.get("larger").is_some());
+    }
+
+    #[test]
+    fn test_approximate_size() {
+        assert_eq!(approximate_size("CSC-CTY", &String::from("computer science")), "CSC-CTY".len() + "\"computer science\"".len());
+    }
+
+    #[test]
+    fn test_ttl_accounts_for_the_age_of_the_response() {
+        let clock = ManualClock::new();
+        let cache_config = CacheConfig { max_stale : Some(Duration::from_secs(30)), ..config(4, Duration::from_secs(60)) };
+        let cache : ResponseCache<String> = ResponseCache::with_clock(cache_config, Arc::new(clock.clone()));
+
+        // already 50 seconds old, i.e. read back from the disk cache
+        cache.insert_with_age("CSC-CTY", String::from("computer science"), Duration::from_secs(50));
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Fresh(String::from("computer science")));
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Stale(String::from("computer science")));
+
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Missing);
+        assert!(cache.entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stale_values_are_loaded_again() {
+        let clock = ManualClock::new();
+        let cache_config = CacheConfig { max_stale : Some(Duration::from_secs(30)), ..config(4, Duration::from_secs(60)) };
+        let cache : ResponseCache<String> = ResponseCache::with_clock(cache_config, Arc::new(clock.clone()));
+        let loaded = cache.get_or_load("CSC-CTY", || async { Ok((String::from("computer science"), Duration::ZERO)) }).await;
+        assert_eq!(loaded.unwrap(), "computer science");
+        let cached = cache.get_or_load("CSC-CTY", || async { Ok((String::from("unused"), Duration::ZERO)) }).await;
+        assert_eq!(cached.unwrap(), "computer science");
+
+        // a stale value isn't served by get_or_load
+        clock.advance(Duration::from_secs(60));
+        let refreshed = cache.get_or_load("CSC-CTY", || async { Ok((String::from("refreshed"), Duration::ZERO)) }).await;
+        assert_eq!(refreshed.unwrap(), "refreshed");
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Fresh(String::from("refreshed")));
+    }
+
+    #[test]
+    fn test_stale_values_are_served_until_max_stale() {
+        let clock = ManualClock::new();
+        let cache_config = CacheConfig { max_stale : Some(Duration::from_secs(30)), ..config(4, Duration::from_secs(60)) };
+        let cache : ResponseCache<String> = ResponseCache::with_clock(cache_config, Arc::new(clock.clone()));
+        cache.insert("CSC-CTY", String::from("computer science"));
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Fresh(String::from("computer science")));
+
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Stale(String::from("computer science")));
+        assert_eq!(cache.get("CSC-CTY"), None);
+
+        // a refreshed value is fresh again
+        cache.insert("CSC-CTY", String::from("refreshed"));
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Fresh(String::from("refreshed")));
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(cache.lookup("CSC-CTY"), CacheLookup::Missing);
+    }
+}
@@ -1,125 +1,28 @@
 // relevant imports
-use reqwest;
 use anyhow::Result;
-use crate::models::{CourseInfo, CourseComponents};
-use serde_json::json;
+use crate::api::sans_io::{self, CoursedogRequest, HttpMethod, COURSEDOG_BASE_URL};
+use crate::api::single_flight::SingleFlight;
 use closestmatch::ClosestMatch;
-use std::borrow::Borrow;
 use std::collections::HashMap;
-use reqwest::header::{HeaderMap, ACCEPT, HeaderValue, CONTENT_TYPE, ORIGIN, REFERER};
+use std::sync::LazyLock;
 
 
 // this will deal with any internal functions related to departments
 
-/// Fetches courses for a specific department
-/// 
-/// # Arguments
-/// * `department_name` - Name of the department
-/// 
-/// # Examples
-/// ```rust
-/// let courses = fetch_courses_by_department("computer science").await?;
-/// ```
-// department_name : this is the user input
-pub async fn fetch_courses_by_department(department_name : &str) -> Result<Vec<CourseInfo>, anyhow::Error> {
-    let mut CourseInfoVector : Vec<CourseInfo> = Vec::new();        // store results here
-    let department_mapping = get_department_mappings();
-    let key_error_handler = String::from("None"); 
-    let department_id = department_mapping.get(&closest_matching_department(&input_validation(department_name))).unwrap_or(key_error_handler.borrow());
-    // let mut course_integer = 0;
-    if department_id == "None" {
-        eprintln!("A department by this name doesn't exist, please refer to the list of departments.");
-        return Err(anyhow::Error::msg("Program Failed"));
-    }
-    let course_info = fetch_courses_by_department_helper(department_id.borrow()).await?["data"].clone();
-
-    // iterator logic (nested loop to bypass the indexing)
-    for courses in course_info.as_array().iter() {
-        for course_data in courses.iter() {
-            let course_group_id : String = serde_json::from_value(course_data["courseGroupId"].clone()).unwrap();
-
-            // println!("current course group id {course_group_id:?}");
-            // if course_group_id.parse::<i32>().is_ok() {
-            //     course_integer = course_group_id.parse().expect("Failed to parse");
-            // } else {
-            //     continue;
-            // }
-            // // let course_integer : i32 = course_group_id.parse().expect("Failed to parse");
-
-            // println!("current course integer : {course_integer:?}");
-            let mut course_component_data : Vec<CourseComponents> = Vec::new();
-            for data in course_data["components"].as_array().iter() {
-                for inner_data in data.iter() {
-                    let mut course_component_instance = CourseComponents {
-                        course_type : serde_json::from_value(inner_data["code"].clone()).unwrap(),
-
-                        weekly_hours : serde_json::from_value(inner_data["contactHours"].clone()).unwrap_or(-1),
-
-                        class_size : serde_json::from_value(inner_data["defaultSectionSize"].clone()).unwrap_or(-1),
-
-                        final_exam : serde_json::from_value(inner_data["finalExamType"].clone()).unwrap(),
-                        attendance_type : serde_json::from_value(inner_data["attendanceType"].clone()).unwrap(),
-
-                        exam_seat_spacing : serde_json::from_value(inner_data["examSeatSpacing"].clone()).unwrap_or(-1),
-
-                        instruction_mode : serde_json::from_value(inner_data["instructionMode"].clone()).unwrap()
-                    };
-
-                    course_component_data.push(course_component_instance);
-                }
-            }
-
-            let effective_end_date_placeholder = serde_json::from_value(course_data["effectiveEndDate"].clone()).unwrap_or("null".to_owned());
-            let course_number_string : String = serde_json::from_value(course_data["courseNumber"].clone()).unwrap();
-
-            let course_number_string_filtered : String = course_number_string.chars().filter(|c| c.is_digit(10)).collect();  // removes any unneccessary values
-            let course_number_integer : i64 = course_number_string_filtered.parse().unwrap_or(64);
-            if course_number_integer == 64 {
-                println!("{:?}",course_number_string_filtered);
-            }
-            // let credits = course_data["credits"]["creditHours"]["max"].clone().to_string();
-            // println!("{credits:?}");
-
-            // let credits_numerical : i32 = 
-            // println!("{:?}",serde_json::from_value(course_data["credits"]["creditHours"]["max"].clone()).unwrap());
-
-            // println!("credits converted val : {credits_converted:?}");
-            let mut course_info_instance = CourseInfo {
-                unique_id : serde_json::from_value(course_data["_id"].clone()).unwrap(),
-
-                course_name : serde_json::from_value(course_data["name"].clone()).unwrap(),
-
-                career : serde_json::from_value(course_data["career"].clone()).unwrap(),
-
-                course_code : serde_json::from_value(course_data["code"].clone()).unwrap(),
-
-                course_components : serde_json::from_value(serde_json::to_value(course_component_data[0].clone()).unwrap().clone()).unwrap(),       // only the first instance is relevant
-
-                effective_start_date : serde_json::from_value(course_data["effectiveStartDate"].clone()).unwrap(),
-
-                effective_end_date : "unknown".to_owned(),      // remains the same throughout
-
-                course_group_id : course_group_id,
+/// Name of the environment variable used to override [`COURSEDOG_BASE_URL`]
+/// (i.e. to point the library at the `coursedog-mock` stand-in server).
+pub const COURSEDOG_BASE_URL_ENV : &str = "CCNY_COURSEDOG_BASE_URL";
 
-                course_number : course_number_integer,
-
-                department : serde_json::from_value(course_data["departments"].clone()).unwrap(),
-
-                subject_code : serde_json::from_value(course_data["subjectCode"].clone()).unwrap(),
-
-                credits : course_data["credits"]["creditHours"]["max"].clone().to_string()
-                
-            };
-            CourseInfoVector.push(course_info_instance);
-        }
-
-    }
-    Ok(CourseInfoVector)
+// returns the base url that requests are built on top of, unless another one is provided (refer to CCNYCourseCatalog::set_base_url)
+// trailing slashes are removed so that paths can be appended as is
+pub fn coursedog_base_url() -> String {
+    std::env::var(COURSEDOG_BASE_URL_ENV)
+        .map(|url| url.trim_end_matches('/').to_owned())
+        .unwrap_or(COURSEDOG_BASE_URL.to_owned())
 }
 
 // helper function to match and filter based on the closest matching string
 pub fn closest_matching_department(user_input_department_name : &str) -> String {
-    let mut result_string : String = String::new();
     let department_list : Vec<String> = get_department_list();        
     let mut min_length = usize::MAX;        // initilize the largest val
 
@@ -132,11 +35,7 @@ pub fn closest_matching_department(user_input_department_name : &str) -> String
         min_length = std::cmp::min(min_length, department_name.len());
     }
 
-    let mut bag_length_vector : Vec<usize> = Vec::new();        // create an empty vector
-    
-    for i in 1..=min_length {
-        bag_length_vector.push(i);      // push the range of numerical values
-    }
+    let bag_length_vector : Vec<usize> = (1..=min_length).collect();      // push the range of numerical values
 
     // create the closestmatching instance string
     let closest_matching_checker = ClosestMatch::new(department_list, bag_length_vector);
@@ -144,96 +43,47 @@ pub fn closest_matching_department(user_input_department_name : &str) -> String
     // search for the string
     // the get_closest method takes in an owned string
     // since it's wrapped around
-    result_string = closest_matching_checker.get_closest(user_input_department_name.to_string()).unwrap();
-    result_string
+    closest_matching_checker.get_closest(user_input_department_name.to_string()).unwrap()
 }
 
 
-// Helper function to fetch courses by department
-pub async fn fetch_courses_by_department_helper(department_code: &str) -> Result<serde_json::Value> {
-
-    // BASE API URL where various query parameters needs to be passed
-    let base_url = "https://app.coursedog.com/api/v1/cm/cty01/courses/search/%24filters";
-    
-    // Query parameters is the same as website, regardless of the department
-    let query_params = [
-        ("catalogId", "tyrc1I8cy2QhVy5W5L2I"),
-        ("skip", "0"),
-        ("limit", "0"),     // set to 0 to ensure all courses are retrieved for a particular department.
-        ("orderBy", "catalogDisplayName,transcriptDescription,longName,name"),
-        ("formatDependents", "false"),
-        ("effectiveDatesRange", "2024-08-28,2024-08-28"),
-        ("columns", "displayName,department,name,courseNumber,subjectCode,code,courseGroupId,credits.creditHours,longName,career,components,customFields.catalogRequirementDesignation,customFields.catalogAttributes")
-    ];
-    
+// department listings currently being downloaded, keyed by base url and department code
+// shared by every catalog instance within the process
+static DEPARTMENT_FETCHES : LazyLock<SingleFlight<serde_json::Value>> = LazyLock::new(SingleFlight::new);
 
-   // serde_json::json is where json! is coming from
-    let payload = json!({
-        "condition": "AND",
-        "filters": [
-            {
-                "condition": "and",
-                "filters": [
-                    {
-                        "id": "status-course",
-                        "name": "status",
-                        "inputType": "select",
-                        "group": "course",
-                        "type": "is",
-                        "value": "Active"
-                    },
-                    {
-                        "id": "catalogPrint-course",
-                        "name": "catalogPrint",
-                        "inputType": "boolean",
-                        "group": "course",
-                        "type": "is",
-                        "value": true
-                    },
-                    {
-                        "id": "career-course",
-                        "name": "career",
-                        "inputType": "careerSelect",
-                        "group": "course",
-                        "type": "is",
-                        "value": "Undergraduate"
-                    },
-                    {
-                        "id": "attributes-course",
-                        "name": "attributes",
-                        "inputType": "attributeSelect",
-                        "group": "course",
-                        "type": "doesNotContain",
-                        "value": ["EXPR - EXPR (Experimental)"]
-                    }
-                ]
-            },
-            {
-                "id": "departments-course",
-                "name": "departments",
-                "inputType": "select",
-                "group": "course",
-                "type": "contains",
-                "value": [department_code]      // this department code is the only thing that
-            }
-        ]
-    });
+// Helper function to fetch courses by department
+// concurrent calls for the same department share a single request
+pub async fn fetch_courses_by_department_helper(base_url : &str, department_code: &str) -> Result<serde_json::Value> {
+    let request = sans_io::department_courses_request(base_url, department_code);
+    DEPARTMENT_FETCHES.run(&format!("{base_url} {department_code}"), || async move {
+        let response_data = send_request(&request).await?;
+        Ok(serde_json::from_str(&response_data)?)
+    }).await
+}
 
-    // Create client and send request
+// executes a request described by the sans-io layer and returns the raw response body
+pub async fn send_request(request : &CoursedogRequest) -> Result<String> {
     let client = reqwest::Client::new();
-    let response = client
-        .post(reqwest::Url::parse_with_params(base_url, &query_params)?)
-        .headers(get_headers())
-        .json(&payload)
-        .send()
-        .await?;
+    let url = request.full_url();
+    let mut request_builder = match request.method {
+        HttpMethod::Get => client.get(url),
+        HttpMethod::Post => client.post(url),
+    };
+
+    for (name, value) in request.headers.iter() {
+        request_builder = request_builder.header(name, value);
+    }
+
+    if let Some(payload) = &request.body {
+        request_builder = request_builder.json(payload);
+    }
 
+    let response = request_builder.send().await?;
     if !response.status().is_success() {
         return Err(anyhow::anyhow!("Failed to fetch courses: {}", response.status()));
     }
 
-    let response_data = response.json().await?;
-    Ok(response_data)
+    Ok(response.text().await?)
 }
 
 // converts string based input to lowercase
@@ -244,7 +94,7 @@ pub fn input_validation(user_input : &str) -> String {
 // Helper function that maps name of departments to their corresponding ID
 pub fn get_department_mappings() -> HashMap<String, String> {
     
-    let department_map = HashMap::from([
+    HashMap::from([
         ("administration".to_owned(), "ADMIN-CTY".to_owned()),
         ("anthropology".to_owned(), "ANTH-CTY".to_owned()),
         ("architecture".to_owned(), "ARCH-CTY".to_owned()),
@@ -286,33 +136,10 @@ pub fn get_department_mappings() -> HashMap<String, String> {
         ("teaching and learning".to_owned(), "TCHLR-CTY".to_owned()),
         ("grove school of engineering".to_owned(), "GROVE-CTY".to_owned()),
         ("theatre and speech".to_owned(), "THSP-CTY".to_owned())
-        ]);
-    department_map
-}
-
-// Helper function to retrieve information about headers
-pub fn get_headers() -> HeaderMap {
-    let mut headers = HeaderMap::new();
-    headers.insert(ACCEPT, HeaderValue::from_static("*/*"));
-    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-    headers.insert("User-Agent", HeaderValue::from_static("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/132.0.0.0 Safari/537.36"));
-    headers.insert("Priority", HeaderValue::from_static("u=1, i"));
-    headers.insert("Pragma", HeaderValue::from_static("no-cache"));
-    headers.insert(ORIGIN, HeaderValue::from_static("https://ccny-undergraduate.catalog.cuny.edu"));
-    headers.insert(REFERER, HeaderValue::from_static("https://ccny-undergraduate.catalog.cuny.edu/"));      // was originally missing, caused error with data retrieval
-    headers.insert("sec-ch-ua", HeaderValue::from_static("\"Not A(Brand\";v=\"8\", \"Chromium\";v=\"132\", \"Google Chrome\";v=\"132\""));
-    headers.insert("sec-ch-ua-mobile", HeaderValue::from_static("?0"));
-    headers.insert("sec-ch-ua-platform", HeaderValue::from_static("\"macOS\""));
-    headers
+        ])
 }
 
 // calls upon get_department_mappings and isolates the keys into a seperate array that is returned
 pub fn get_department_list() -> Vec<String> {
-    let department_mapping = get_department_mappings();
-    let mut department_list = Vec::new();
-    for (key,value) in department_mapping.into_iter() {
-        department_list.push(String::from(key));
-    }
-
-    department_list
+    get_department_mappings().into_keys().collect()
 }
\ No newline at end of file
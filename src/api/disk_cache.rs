@@ -0,0 +1,350 @@
+// on-disk cache of the responses retrieved by CCNYCourseCatalog
+// unlike the in-memory cache, the responses survive process restarts (i.e. between runs of a command line tool)
+//
+// layout of the cache directory
+// {directory}/{catalog id}/{api}/{effective dates range}/{kind}/{key}.json
+// i.e. ~/.cache/ccny/tyrc1I8cy2QhVy5W5L2I/app.coursedog.com_api_v1/2024-08-28_2024-08-28/departments/CSC-CTY.json
+//
+// {api} is the base url the responses were downloaded from (without the scheme), so that responses
+// of the coursedog-mock stand-in are never served to a catalog talking to the real api (and vice versa)
+//
+// every file stores the time the response was downloaded at along with the response itself
+//
+// the directory may be shared with other files (i.e. `--cache-dir .`), so clearing and evicting
+// only ever touches response files within {directory}/{catalog id}/{api}/
+use anyhow::Result;
+use crate::api::sans_io::{DEFAULT_CATALOG_ID, DEFAULT_EFFECTIVE_DATES_RANGE};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// distinguishes the temporary files of concurrent writes within the same process
+static TEMPORARY_FILE_COUNTER : AtomicU64 = AtomicU64::new(0);
+
+/// Configuration of the optional on-disk response cache used by [`crate::CCNYCourseCatalog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCacheConfig {
+    /// directory the responses are stored in (within a sub directory owned by the cache), created on the first write
+    pub directory : PathBuf,
+
+    /// how long a stored response remains valid
+    pub ttl : Duration,
+
+    /// once the stored responses take up more than this many bytes, the oldest ones are deleted
+    pub max_bytes : u64,
+}
+
+impl DiskCacheConfig {
+    /// responses are kept for a day, using up to 64 MiB
+    pub fn new(directory : impl Into<PathBuf>) -> Self {
+        DiskCacheConfig {
+            directory : directory.into(),
+            ttl : Duration::from_secs(24 * 60 * 60),
+            max_bytes : 64 * 1024 * 1024,
+        }
+    }
+}
+
+// kinds of responses stored within the cache, each is stored within it's own sub directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    Departments,
+    CourseDetails,
+}
+
+impl ResponseKind {
+    const ALL : [ResponseKind; 2] = [ResponseKind::Departments, ResponseKind::CourseDetails];
+
+    fn directory_name(self) -> &'static str {
+        match self {
+            ResponseKind::Departments => "departments",
+            ResponseKind::CourseDetails => "course_details",
+        }
+    }
+}
+
+// contents of a single cache file
+#[derive(Serialize, Deserialize)]
+struct StoredResponse<V> {
+    // seconds since the unix epoch
+    fetched_at : u64,
+    value : V,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    config : DiskCacheConfig,
+
+    // sanitized base url of the api the responses are downloaded from
+    api : String,
+}
+
+impl DiskCache {
+    // base_url : api the stored responses are downloaded from, responses of other apis are kept separately
+    pub fn new(config : DiskCacheConfig, base_url : &str) -> Self {
+        let mut disk_cache = DiskCache { config, api : String::new() };
+        disk_cache.set_base_url(base_url);
+        disk_cache
+    }
+
+    pub fn set_base_url(&mut self, base_url : &str) {
+        let without_scheme = base_url.split_once("://").map_or(base_url, |(_, rest)| rest);
+        self.api = sanitize(without_scheme.trim_end_matches('/'));
+    }
+
+    // returns the stored response along with how long ago it was downloaded, unless it's missing, expired or can't be read
+    // a cache that can't be read is treated the same way as an empty one, so the response is downloaded again
+    pub async fn read<V : DeserializeOwned>(&self, kind : ResponseKind, key : &str) -> Option<(V, Duration)> {
+        let contents = tokio::fs::read(self.path(kind, key)).await.ok()?;
+        let stored : StoredResponse<V> = serde_json::from_slice(&contents).ok()?;
+
+        let age = unix_timestamp().saturating_sub(stored.fetched_at);
+        if age >= self.config.ttl.as_secs() {
+            return None;
+        }
+
+        Some((stored.value, Duration::from_secs(age)))
+    }
+
+    // stores the response, deleting the oldest responses if the size limit is exceeded
+    pub async fn write<V : Serialize>(&self, kind : ResponseKind, key : &str, value : &V) -> Result<()> {
+        let path = self.path(kind, key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let stored = StoredResponse {
+            fetched_at : unix_timestamp(),
+            value,
+        };
+
+        // written to a temporary file first, so a concurrent read never sees a partially written response
+        // every write uses it's own temporary file, so concurrent writes of the same key don't rename each other's file
+        let temporary_path = path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        tokio::fs::write(&temporary_path, serde_json::to_vec(&stored)?).await?;
+        tokio::fs::rename(&temporary_path, &path).await?;
+
+        self.enforce_size_limit().await
+    }
+
+    // removes every stored response, along with the directories of the cache once they are empty
+    // anything else within the configured directory is left alone
+    pub fn clear(&self) -> Result<()> {
+        for file in stored_files_blocking(&self.root())? {
+            match std::fs::remove_file(&file.path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                _ => {},
+            }
+
+            // directories still holding other files are kept
+            // (the kind, the effective dates range, the api and the catalog id, in that order)
+            for directory in file.path.ancestors().skip(1).take(4) {
+                if std::fs::remove_dir(directory).is_err() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // sub directory owned by the cache, i.e. {directory}/{catalog id}/{api}
+    fn root(&self) -> PathBuf {
+        self.config.directory.join(sanitize(DEFAULT_CATALOG_ID)).join(&self.api)
+    }
+
+    fn path(&self, kind : ResponseKind, key : &str) -> PathBuf {
+        self.root()
+            .join(sanitize(DEFAULT_EFFECTIVE_DATES_RANGE))
+            .join(kind.directory_name())
+            .join(format!("{}.json", sanitize(key)))
+    }
+
+    // deletes the least recently written responses until the cache fits within max_bytes
+    async fn enforce_size_limit(&self) -> Result<()> {
+        let root = self.root();
+        let mut files = tokio::task::spawn_blocking(move || stored_files_blocking(&root)).await??;
+        let mut total_size : u64 = files.iter().map(|file| file.size).sum();
+        if total_size <= self.config.max_bytes {
+            return Ok(());
+        }
+
+        files.sort_by_key(|file| file.modified);
+        for file in files {
+            if total_size <= self.config.max_bytes {
+                break;
+            }
+
+            // another process may have already deleted the file
+            match tokio::fs::remove_file(&file.path).await {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                _ => total_size = total_size.saturating_sub(file.size),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct StoredFile {
+    path : PathBuf,
+    size : u64,
+    modified : SystemTime,
+}
+
+// every response file written by the cache, i.e. {root}/{effective dates range}/{kind}/{key}.json
+// along with temporary files left behind by interrupted writes
+fn stored_files_blocking(root : &Path) -> Result<Vec<StoredFile>> {
+    let mut files = Vec::new();
+    for range_entry in read_directory(root)? {
+        let range_entry = range_entry?;
+        if !range_entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        for kind in ResponseKind::ALL {
+            for entry in read_directory(&range_entry.path().join(kind.directory_name()))? {
+                let entry = entry?;
+                let path = entry.path();
+                let is_response = path.extension().is_some_and(|extension| extension == "json" || extension == "tmp");
+                let metadata = entry.metadata()?;
+                if is_response && metadata.is_file() {
+                    files.push(StoredFile {
+                        path,
+                        size : metadata.len(),
+                        modified : metadata.modified().unwrap_or(UNIX_EPOCH),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+// entries of the directory, none if it doesn't exist
+fn read_directory(directory : &Path) -> Result<Vec<std::io::Result<std::fs::DirEntry>>> {
+    match std::fs::read_dir(directory) {
+        Ok(entries) => Ok(entries.collect()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// keys are turned into file names, anything other than letters, digits, '-' and '.' is replaced
+fn sanitize(key : &str) -> String {
+    key.chars()
+        .map(|character| if character.is_ascii_alphanumeric() || character == '-' || character == '.' { character } else { '_' })
+        .collect()
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::sans_io::COURSEDOG_BASE_URL;
+
+    fn size(cache : &DiskCache) -> u64 {
+        stored_files_blocking(&cache.root()).unwrap().iter().map(|file| file.size).sum()
+    }
+
+    // every test uses it's own directory, since the tests run concurrently
+    fn test_cache(name : &str, max_bytes : u64) -> DiskCache {
+        let directory = std::env::temp_dir().join(format!("ccny-disk-cache-{name}-{}", std::process::id()));
+        let cache = DiskCache::new(DiskCacheConfig { max_bytes, ..DiskCacheConfig::new(directory) }, COURSEDOG_BASE_URL);
+        cache.clear().unwrap();
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_write_and_read() {
+        let cache = test_cache("roundtrip", 1024 * 1024);
+        assert_eq!(cache.read::<Vec<String>>(ResponseKind::Departments, "CSC-CTY").await, None);
+
+        cache.write(ResponseKind::Departments, "CSC-CTY", &vec!["Data Structures"]).await.unwrap();
+        let (courses, age) : (Vec<String>, Duration) = cache.read(ResponseKind::Departments, "CSC-CTY").await.unwrap();
+        assert_eq!(courses, vec![String::from("Data Structures")]);
+        assert!(age < Duration::from_secs(60));
+
+        // the kinds are stored separately
+        assert_eq!(cache.read::<Vec<String>>(ResponseKind::CourseDetails, "CSC-CTY").await, None);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.read::<Vec<String>>(ResponseKind::Departments, "CSC-CTY").await, None);
+        assert_eq!(size(&cache), 0);
+    }
+
+    #[tokio::test]
+    async fn test_expired_responses_are_ignored() {
+        let mut cache = test_cache("expired", 1024 * 1024);
+        cache.config.ttl = Duration::ZERO;
+        cache.write(ResponseKind::CourseDetails, "0455351", &"Data Structures").await.unwrap();
+        assert_eq!(cache.read::<String>(ResponseKind::CourseDetails, "0455351").await, None);
+        cache.clear().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_oldest_responses_are_deleted_past_size_limit() {
+        let cache = test_cache("size-limit", 300);
+        let listing = "x".repeat(100);
+        let written_at = SystemTime::now() - Duration::from_secs(60);
+        for (index, department) in ["BIO-CTY", "CSC-CTY", "EE-CTY"].into_iter().enumerate() {
+            cache.write(ResponseKind::Departments, department, &listing).await.unwrap();
+
+            // modification times are set explicitly, filesystems may not tell apart writes made in quick succession
+            let file = std::fs::File::options().write(true).open(cache.path(ResponseKind::Departments, department)).unwrap();
+            file.set_modified(written_at + Duration::from_secs(index as u64)).unwrap();
+        }
+
+
+        assert!(size(&cache) <= 300);
+        assert_eq!(cache.read::<String>(ResponseKind::Departments, "BIO-CTY").await, None);
+        assert_eq!(cache.read::<String>(ResponseKind::Departments, "EE-CTY").await.map(|(listing, _)| listing), Some(listing));
+        cache.clear().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_apis_are_stored_separately() {
+        let mut cache = test_cache("apis", 1024 * 1024);
+        cache.write(ResponseKind::Departments, "CSC-CTY", &"production").await.unwrap();
+
+        let mut stand_in = cache.clone();
+        stand_in.set_base_url("http://127.0.0.1:8787/");
+        assert_eq!(stand_in.read::<String>(ResponseKind::Departments, "CSC-CTY").await, None);
+        stand_in.write(ResponseKind::Departments, "CSC-CTY", &"stand-in").await.unwrap();
+
+        // clearing the responses of one api leaves the other's alone
+        stand_in.clear().unwrap();
+        cache.set_base_url(&format!("{COURSEDOG_BASE_URL}/"));
+        assert_eq!(cache.read::<String>(ResponseKind::Departments, "CSC-CTY").await.map(|(value, _)| value), Some(String::from("production")));
+        cache.clear().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_other_files_are_left_alone() {
+        let cache = test_cache("other-files", 150);
+        let directory = &cache.config.directory;
+        std::fs::create_dir_all(directory.join("notes")).unwrap();
+        std::fs::write(directory.join("package.json"), "x".repeat(200)).unwrap();
+        std::fs::write(directory.join("notes").join("todo.json"), "{}").unwrap();
+
+        // unrelated files neither count towards the limit nor get evicted
+        cache.write(ResponseKind::Departments, "CSC-CTY", &"x".repeat(50)).await.unwrap();
+        assert!(cache.read::<String>(ResponseKind::Departments, "CSC-CTY").await.is_some());
+
+        cache.clear().unwrap();
+        assert!(!directory.join(sanitize(DEFAULT_CATALOG_ID)).exists());
+        assert!(directory.join("package.json").exists() && directory.join("notes").join("todo.json").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
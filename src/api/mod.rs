@@ -1,3 +1,6 @@
+pub mod cache;
 pub mod department;
+pub mod disk_cache;
 pub mod course_finder;
-pub mod utils;
\ No newline at end of file
+pub mod sans_io;
+pub mod single_flight;
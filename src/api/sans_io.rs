@@ -0,0 +1,536 @@
+// Sans-IO layer of the wrapper
+// everything in here is free of network calls, the functions either describe a request that needs to be sent
+// or convert a response body that has already been received into the models
+// this allows the same logic to be reused with any http client (wasm fetch, queue based fetchers, etc.)
+// nothing is read from the environment either, the base url is provided by the caller
+use anyhow::{Context, Result};
+use closestmatch::ClosestMatch;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::api::department::{closest_matching_department, get_department_mappings, input_validation};
+use crate::data_structures::custom_hashmap::{CustomHashMap, FnvBuildHasher};
+use crate::models::{CourseComponents, CourseDetail, CourseInfo, Term};
+
+/// Base URL of the Coursedog API that serves CCNY's catalog data.
+pub const COURSEDOG_BASE_URL : &str = "https://app.coursedog.com/api/v1";
+
+/// Catalog that is queried when retrieving list of courses.
+pub const DEFAULT_CATALOG_ID : &str = "tyrc1I8cy2QhVy5W5L2I";
+
+/// Date range used to determine which revision of a course is effective.
+pub const DEFAULT_EFFECTIVE_DATES_RANGE : &str = "2024-08-28,2024-08-28";
+
+// columns requested when retrieving the list of courses for a department
+const DEPARTMENT_COURSE_COLUMNS : &str = "displayName,department,name,courseNumber,subjectCode,code,courseGroupId,credits.creditHours,longName,career,components,customFields.catalogRequirementDesignation,customFields.catalogAttributes";
+
+// columns requested when retrieving information about a particular course
+const COURSE_DETAIL_COLUMNS : &str = "departments,courseTypicallyOffered,career,credits,components,topics,catalogAttributes,description,requirementGroup,courseSchedule,customFields.ZK6fC,longName,institution,consent,customFields.cuPathwaysAttribute,subjectCode,courseNumber,customFields.cuLibartsFlag,code,name,college,status,institutionId,rawCourseId,crseOfferNbr,customFields.catalogAttributes,customFields.rawCourseId";
+
+/// HTTP method of a [`CoursedogRequest`].
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post
+}
+
+/// Description of a request to the Coursedog API, to be executed by any HTTP client.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CoursedogRequest {
+    pub method : HttpMethod,
+    pub url : String,                           // url without the query parameters
+    pub query_params : Vec<(String, String)>,
+    pub headers : Vec<(String, String)>,
+    pub body : Option<Value>                    // json payload, if any
+}
+
+impl CoursedogRequest {
+    /// Returns the url with the query parameters percent encoded (as `application/x-www-form-urlencoded`) and appended.
+    pub fn full_url(&self) -> String {
+        if self.query_params.is_empty() {
+            return self.url.clone();
+        }
+
+        let query : Vec<String> = self.query_params.iter()
+            .map(|(key, value)| format!("{}={}", form_urlencode(key), form_urlencode(value)))
+            .collect();
+        format!("{}?{}", self.url, query.join("&"))
+    }
+}
+
+// alphanumeric characters and "*-._" are kept as is, spaces become '+' and every other byte is percent encoded
+fn form_urlencode(value : &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => encoded.push(byte as char),
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+// appends the path of an endpoint to the base url, ignoring trailing slashes of the base url
+fn endpoint(base_url : &str, path : &str) -> String {
+    format!("{}{path}", base_url.trim_end_matches('/'))
+}
+
+/// Headers sent along with every request, mimicking the public catalog website.
+pub fn default_headers() -> Vec<(String, String)> {
+    [
+        ("Accept", "*/*"),
+        ("Content-Type", "application/json"),
+        ("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/132.0.0.0 Safari/537.36"),
+        ("Priority", "u=1, i"),
+        ("Pragma", "no-cache"),
+        ("Origin", "https://ccny-undergraduate.catalog.cuny.edu"),
+        ("Referer", "https://ccny-undergraduate.catalog.cuny.edu/"),      // was originally missing, caused error with data retrieval
+        ("sec-ch-ua", "\"Not A(Brand\";v=\"8\", \"Chromium\";v=\"132\", \"Google Chrome\";v=\"132\""),
+        ("sec-ch-ua-mobile", "?0"),
+        ("sec-ch-ua-platform", "\"macOS\""),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_owned(), value.to_owned()))
+    .collect()
+}
+
+// converts a list of borrowed pairs into owned pairs
+fn owned_params(params : &[(&str, &str)]) -> Vec<(String, String)> {
+    params.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
+
+/// Resolves a user provided department name (i.e. "comp sci") to its department id (i.e. "CSC-CTY").
+pub fn resolve_department_code(department_name : &str) -> Option<String> {
+    let department_mapping = get_department_mappings();
+    department_mapping.get(&closest_matching_department(&input_validation(department_name))).cloned()
+}
+
+/// Builds the request that retrieves every course offered by a department.
+///
+/// # Arguments
+/// * `base_url` - base url of the Coursedog API, usually [`COURSEDOG_BASE_URL`]
+/// * `department_code` - id of the department (i.e. "CSC-CTY"), see [`resolve_department_code`]
+pub fn department_courses_request(base_url : &str, department_code : &str) -> CoursedogRequest {
+    // Query parameters is the same as website, regardless of the department
+    let query_params = [
+        ("catalogId", DEFAULT_CATALOG_ID),
+        ("skip", "0"),
+        ("limit", "0"),     // set to 0 to ensure all courses are retrieved for a particular department.
+        ("orderBy", "catalogDisplayName,transcriptDescription,longName,name"),
+        ("formatDependents", "false"),
+        ("effectiveDatesRange", DEFAULT_EFFECTIVE_DATES_RANGE),
+        ("columns", DEPARTMENT_COURSE_COLUMNS)
+    ];
+
+    let payload = json!({
+        "condition": "AND",
+        "filters": [
+            {
+                "condition": "and",
+                "filters": [
+                    {
+                        "id": "status-course",
+                        "name": "status",
+                        "inputType": "select",
+                        "group": "course",
+                        "type": "is",
+                        "value": "Active"
+                    },
+                    {
+                        "id": "catalogPrint-course",
+                        "name": "catalogPrint",
+                        "inputType": "boolean",
+                        "group": "course",
+                        "type": "is",
+                        "value": true
+                    },
+                    {
+                        "id": "career-course",
+                        "name": "career",
+                        "inputType": "careerSelect",
+                        "group": "course",
+                        "type": "is",
+                        "value": "Undergraduate"
+                    },
+                    {
+                        "id": "attributes-course",
+                        "name": "attributes",
+                        "inputType": "attributeSelect",
+                        "group": "course",
+                        "type": "doesNotContain",
+                        "value": ["EXPR - EXPR (Experimental)"]
+                    }
+                ]
+            },
+            {
+                "id": "departments-course",
+                "name": "departments",
+                "inputType": "select",
+                "group": "course",
+                "type": "contains",
+                "value": [department_code]      // this department code is the only thing that changes between requests
+            }
+        ]
+    });
+
+    CoursedogRequest {
+        method : HttpMethod::Post,
+        url : endpoint(base_url, "/cm/cty01/courses/search/%24filters"),
+        query_params : owned_params(&query_params),
+        headers : default_headers(),
+        body : Some(payload)
+    }
+}
+
+/// Prepends the leading 0 that gets lost when a course group id is treated as a number.
+pub fn normalize_course_group_id(course_group_id : &str) -> String {
+    // NOTE : if the course group id is 7 characters long, we don't have to prepend a 0 to the existing string
+    // otherwise however we do have to prepend a 0 to the string
+    // to ensure that all courses can be searched
+    if course_group_id.len() < 7 {
+        format!("0{course_group_id}")
+    } else {
+        course_group_id.to_owned()
+    }
+}
+
+/// Builds the request that retrieves detailed information about a particular course.
+///
+/// # Arguments
+/// * `base_url` - base url of the Coursedog API, usually [`COURSEDOG_BASE_URL`]
+/// * `course_group_id` - id shared by every revision of the course (i.e. "0455351")
+pub fn course_detail_request(base_url : &str, course_group_id : &str) -> CoursedogRequest {
+    let course_group_id = normalize_course_group_id(course_group_id);
+    let query_params = [
+        ("courseGroupIds", course_group_id.as_str()),
+        ("effectiveDatesRange", DEFAULT_EFFECTIVE_DATES_RANGE),
+
+        // below statements can be the same throughout (meaning they are static query params)
+        ("formatDependents", "false"),
+        ("includeRelatedData", "true"),
+        ("includeCrosslisted", "false"),
+        ("includeCourseEquivalencies", "true"),
+        ("includePending", "false"),
+        ("includeMappedDocumentItems", "true"),
+        ("returnResultsWithTotalCount", "false"),
+        ("doNotDisplayAllMappedRevisionsAsDependencies", "true"),
+        ("columns", COURSE_DETAIL_COLUMNS)
+    ];
+
+    // NOTE : there's no payload involved for this request
+    CoursedogRequest {
+        method : HttpMethod::Post,
+        url : endpoint(base_url, "/cm/cty01/courses/search/$filters"),
+        query_params : owned_params(&query_params),
+        headers : default_headers(),
+        body : None
+    }
+}
+
+/// Builds the request that retrieves all the historical terms.
+///
+/// # Arguments
+/// * `base_url` - base url of the Coursedog API, usually [`COURSEDOG_BASE_URL`]
+pub fn terms_request(base_url : &str) -> CoursedogRequest {
+    CoursedogRequest {
+        method : HttpMethod::Get,
+        url : endpoint(base_url, "/cty01/general/terms"),
+        query_params : Vec::new(),
+        headers : Vec::new(),
+        body : None
+    }
+}
+
+/// Parses the body of a [`department_courses_request`] response into a list of courses.
+pub fn parse_department_courses(body : &str) -> Result<Vec<CourseInfo>> {
+    let response : Value = serde_json::from_str(body)?;
+    department_courses_from_value(&response)
+}
+
+/// Same as [`parse_department_courses`], for responses that have already been deserialized.
+pub fn department_courses_from_value(response : &Value) -> Result<Vec<CourseInfo>> {
+    response_data(response)?.iter().map(course_info_from_value).collect()
+}
+
+/// Parses the body of a [`course_detail_request`] response into the raw json returned by the api.
+pub fn parse_course_info(body : &str) -> Result<Value> {
+    Ok(serde_json::from_str(body)?)
+}
+
+/// Parses the body of a [`course_detail_request`] response into the models.
+pub fn parse_course_details(body : &str) -> Result<Vec<CourseDetail>> {
+    let response : Value = serde_json::from_str(body)?;
+    course_details_from_value(&response)
+}
+
+/// Same as [`parse_course_details`], for responses that have already been deserialized.
+pub fn course_details_from_value(response : &Value) -> Result<Vec<CourseDetail>> {
+    response_data(response)?.iter().map(course_detail_from_value).collect()
+}
+
+/// Parses the body of a [`terms_request`] response into a list of terms.
+pub fn parse_terms(body : &str) -> Result<Vec<Term>> {
+    let response : Value = serde_json::from_str(body)?;
+
+    // terms may be returned as a list, as a list wrapped within "data", or keyed by their id
+    let terms : Vec<(Option<&String>, &Value)> = match &response {
+        Value::Array(terms) => terms.iter().map(|term| (None, term)).collect(),
+        Value::Object(object) => match object.get("data") {
+            Some(Value::Array(terms)) => terms.iter().map(|term| (None, term)).collect(),
+            _ => object.iter().map(|(id, term)| (Some(id), term)).collect(),
+        },
+        _ => return Err(anyhow::anyhow!("unexpected terms response : {response}")),
+    };
+
+    Ok(terms
+        .into_iter()
+        .filter(|(_, term)| term.is_object())
+        .map(|(key, term)| Term {
+            id : first_string_field(term, &["id", "_id", "code"]).or(key.cloned()).unwrap_or_default(),
+            name : first_string_field(term, &["name", "displayName"]).unwrap_or_default(),
+            start_date : string_field(term, "startDate"),
+            end_date : string_field(term, "endDate"),
+        })
+        .collect())
+}
+
+/// Finds the course group id of the course whose name is the closest match to `course_name`.
+///
+/// Returns `None` if `courses` is empty.
+pub fn find_course_group_id(courses : &[CourseInfo], course_name : &str) -> Option<String> {
+    let mut course_name_list : Vec<String> = Vec::new();    // isolates name of courses based on the retrieved data
+    // maps course name to course group ID, the keys come from the catalog itself so the faster hasher is used
+    let mut course_name_and_id_map : CustomHashMap<String, String, FnvBuildHasher> = CustomHashMap::with_capacity_and_hasher(courses.len(), FnvBuildHasher::default());
+    let mut smallest_course_length = usize::MAX;        // stores the length of smallest course
+
+    // isolate the courses and store them within course_name_list vector
+    // form the hashmap as well
+    for course_data in courses.iter() {
+        course_name_list.push(course_data.course_name.to_lowercase());
+        course_name_and_id_map.insert(course_data.course_name.to_lowercase(), course_data.course_group_id.clone());
+        smallest_course_length = std::cmp::min(smallest_course_length, course_data.course_name.len());
+    }
+
+    if course_name_list.is_empty() {
+        return None;
+    }
+
+    // create the bag of word array and search for the closest matching course
+    let bag_of_words : Vec<usize> = (0..=smallest_course_length).collect();
+    let course_search_engine = ClosestMatch::new(course_name_list, bag_of_words);
+    let closest_matching_course = course_search_engine.get_closest(course_name.to_lowercase())?;
+    course_name_and_id_map.remove(&closest_matching_course)
+}
+
+// every search response wraps the list of results within "data"
+fn response_data(response : &Value) -> Result<&Vec<Value>> {
+    response["data"]
+        .as_array()
+        .with_context(|| format!("expected a \"data\" array within the response, received : {response}"))
+}
+
+fn course_info_from_value(course_data : &Value) -> Result<CourseInfo> {
+    let course_code = string_field(course_data, "code");
+    let course_components = course_data["components"]
+        .as_array()
+        .and_then(|components| components.first())      // only the first instance is relevant
+        .map(course_components_from_value)
+        .transpose()?
+        .with_context(|| format!("course {course_code:?} has no components"))?;
+
+    // removes any unneccessary values (i.e. "21200A" -> 21200)
+    let course_number_string_filtered : String = string_field(course_data, "courseNumber").chars().filter(|c| c.is_ascii_digit()).collect();
+
+    Ok(CourseInfo {
+        unique_id : required_string_field(course_data, "_id")?,
+        course_name : required_string_field(course_data, "name")?,
+        career : string_field(course_data, "career"),
+        course_code,
+        course_components,
+        effective_start_date : string_field(course_data, "effectiveStartDate"),
+        effective_end_date : "unknown".to_owned(),      // remains the same throughout
+        course_group_id : required_string_field(course_data, "courseGroupId")?,
+        course_number : course_number_string_filtered.parse().unwrap_or(64),
+        department : serde_json::from_value(course_data["departments"].clone()).unwrap_or_default(),
+        subject_code : string_field(course_data, "subjectCode"),
+        credits : course_data["credits"]["creditHours"]["max"].to_string()
+    })
+}
+
+fn course_components_from_value(component : &Value) -> Result<CourseComponents> {
+    Ok(CourseComponents {
+        course_type : required_string_field(component, "code")?,
+        attendance_type : string_field(component, "attendanceType"),
+        weekly_hours : integer_field(component, "contactHours"),
+        class_size : integer_field(component, "defaultSectionSize"),
+        final_exam : string_field(component, "finalExamType"),
+        exam_seat_spacing : integer_field(component, "examSeatSpacing"),
+        instruction_mode : string_field(component, "instructionMode")
+    })
+}
+
+fn course_detail_from_value(course_data : &Value) -> Result<CourseDetail> {
+    let course_components = course_data["components"]
+        .as_array()
+        .map(|components| components.iter().map(course_components_from_value).collect::<Result<Vec<_>>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    // departments are returned as objects when retrieving a particular course
+    let departments = course_data["departments"]
+        .as_array()
+        .map(|departments| {
+            departments
+                .iter()
+                .filter_map(|department| department.as_str().or(department["id"].as_str()).map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(CourseDetail {
+        unique_id : required_string_field(course_data, "_id")?,
+        course_group_id : required_string_field(course_data, "courseGroupId")?,
+        course_code : string_field(course_data, "code"),
+        course_name : string_field(course_data, "name"),
+        long_name : string_field(course_data, "longName"),
+        description : string_field(course_data, "description"),
+        career : string_field(course_data, "career"),
+        college : string_field(course_data, "college"),
+        subject_code : string_field(course_data, "subjectCode"),
+        course_number : string_field(course_data, "courseNumber"),
+        credits : course_data["credits"]["creditHours"]["max"].to_string(),
+        course_components,
+        typically_offered : string_field(course_data, "courseTypicallyOffered"),
+        consent : string_field(course_data, "consent"),
+        departments,
+        requirement_group : string_field(course_data, "requirementGroup"),
+        status : string_field(course_data, "status")
+    })
+}
+
+// missing or null fields are treated as empty strings
+fn string_field(data : &Value, key : &str) -> String {
+    data[key].as_str().unwrap_or_default().to_owned()
+}
+
+fn first_string_field(data : &Value, keys : &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| data[*key].as_str().map(String::from))
+}
+
+fn required_string_field(data : &Value, key : &str) -> Result<String> {
+    data[key]
+        .as_str()
+        .map(String::from)
+        .with_context(|| format!("missing {key:?} within {data}"))
+}
+
+// missing numerical values are represented as -1
+fn integer_field(data : &Value, key : &str) -> i32 {
+    data[key].as_i64().and_then(|value| i32::try_from(value).ok()).unwrap_or(-1)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_course() -> Value {
+        json!({
+            "_id" : "0455351-1901-01-01",
+            "name" : "Data Structures",
+            "longName" : "Data Structures",
+            "career" : "Undergraduate",
+            "code" : "CSC 21200",
+            "college" : "ENGR - Grove School of Engineering",
+            "components" : [{
+                "code" : "LEC",
+                "contactHours" : 4,
+                "defaultSectionSize" : 35,
+                "finalExamType" : "Yes",
+                "attendanceType" : "Class Meeting",
+                "examSeatSpacing" : 1,
+                "instructionMode" : "In Person"
+            }],
+            "effectiveStartDate" : "1901-01-01",
+            "effectiveEndDate" : null,
+            "courseGroupId" : "0455351",
+            "courseNumber" : "21200",
+            "departments" : ["CSC-CTY"],
+            "subjectCode" : "CSC",
+            "credits" : { "creditHours" : { "max" : 3, "min" : 3 } },
+            "description" : "Extension of the knowledge of algorithm design and programming gained in CSC 10300."
+        })
+    }
+
+    #[test]
+    fn test_department_courses_request() {
+        let request = department_courses_request(COURSEDOG_BASE_URL, "CSC-CTY");
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.url, "https://app.coursedog.com/api/v1/cm/cty01/courses/search/%24filters");
+        assert_eq!(request.body.unwrap()["filters"][1]["value"][0], "CSC-CTY");
+        assert!(request.query_params.contains(&("catalogId".to_owned(), DEFAULT_CATALOG_ID.to_owned())));
+    }
+
+    #[test]
+    fn test_course_detail_request_pads_group_id() {
+        let request = course_detail_request("http://127.0.0.1:8787/", "455351");
+        assert_eq!(request.url, "http://127.0.0.1:8787/cm/cty01/courses/search/$filters");
+        assert!(request.query_params.contains(&("courseGroupIds".to_owned(), "0455351".to_owned())));
+        assert!(request.body.is_none());
+    }
+
+    #[test]
+    fn test_full_url() {
+        let mut request = terms_request(COURSEDOG_BASE_URL);
+        assert_eq!(request.full_url(), "https://app.coursedog.com/api/v1/cty01/general/terms");
+
+        request.query_params = vec![
+            ("columns".to_owned(), "code,credits.creditHours".to_owned()),
+            ("effectiveDatesRange".to_owned(), "2024-08-28,2024-08-28".to_owned()),
+            ("q".to_owned(), "data structures & algorithms".to_owned()),
+        ];
+        assert_eq!(request.full_url(), "https://app.coursedog.com/api/v1/cty01/general/terms?columns=code%2Ccredits.creditHours&effectiveDatesRange=2024-08-28%2C2024-08-28&q=data+structures+%26+algorithms");
+    }
+
+    #[test]
+    fn test_parse_department_courses() {
+        let body = json!({ "data" : [sample_course()], "limit" : 0, "skip" : 0 }).to_string();
+        let courses = parse_department_courses(&body).unwrap();
+        assert_eq!(courses.len(), 1);
+        assert_eq!(courses[0].course_code, "CSC 21200");
+        assert_eq!(courses[0].course_number, 21200);
+        assert_eq!(courses[0].credits, "3");
+        assert_eq!(courses[0].course_components.weekly_hours, 4);
+        assert_eq!(courses[0].department, vec!["CSC-CTY".to_owned()]);
+    }
+
+    #[test]
+    fn test_parse_course_details() {
+        let mut course = sample_course();
+        course["departments"] = json!([{ "id" : "CSC-CTY", "name" : "Computer Science" }]);
+        let details = parse_course_details(&json!({ "data" : [course] }).to_string()).unwrap();
+        assert_eq!(details[0].departments, vec!["CSC-CTY".to_owned()]);
+        assert!(details[0].description.starts_with("Extension"));
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_data() {
+        assert!(parse_department_courses("{}").is_err());
+    }
+
+    #[test]
+    fn test_parse_terms() {
+        let terms = parse_terms(&json!([{ "id" : "1249", "name" : "2024 Fall Term", "startDate" : "2024-08-28" }]).to_string()).unwrap();
+        assert_eq!(terms[0].name, "2024 Fall Term");
+
+        let keyed_terms = parse_terms(&json!({ "1252" : { "name" : "2025 Spring Term" } }).to_string()).unwrap();
+        assert_eq!(keyed_terms[0].id, "1252");
+    }
+
+    #[test]
+    fn test_find_course_group_id() {
+        let courses = department_courses_from_value(&json!({ "data" : [sample_course()] })).unwrap();
+        assert_eq!(find_course_group_id(&courses, "data structures"), Some("0455351".to_owned()));
+        assert_eq!(find_course_group_id(&[], "data structures"), None);
+    }
+}
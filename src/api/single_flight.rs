@@ -0,0 +1,122 @@
+// request coalescing (also known as single flight)
+// if the same department is requested by several tasks at the same time, only the first one sends the request
+// the remaining tasks wait for it to complete and receive a copy of the same result
+//
+// the requests are only shared while they are in flight, the result isn't kept around once it has been delivered
+// (that is the job of the response cache)
+use crate::data_structures::custom_hashmap::CustomHashMap;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+// anyhow::Error isn't Clone, so the error is shared between the waiting tasks behind an Arc
+type SharedFetch<V> = Shared<BoxFuture<'static, Result<V, Arc<anyhow::Error>>>>;
+
+pub struct SingleFlight<V> {
+    in_flight : Arc<Mutex<CustomHashMap<String, SharedFetch<V>>>>,
+}
+
+impl<V : Clone + Send + Sync + 'static> SingleFlight<V> {
+    pub fn new() -> Self {
+        SingleFlight {
+            in_flight : Arc::new(Mutex::new(CustomHashMap::new())),
+        }
+    }
+
+    // runs the future returned by fetch, unless a request with the same key is already in flight
+    // in which case the result of that request is awaited instead (and fetch is never called)
+    //
+    // if every task waiting on a request is cancelled, the next caller resumes the same request
+    pub async fn run<F, Fut>(&self, key : &str, fetch : F) -> anyhow::Result<V>
+    where
+        F : FnOnce() -> Fut,
+        Fut : Future<Output = anyhow::Result<V>> + Send + 'static,
+    {
+        let shared_fetch = {
+            let mut in_flight = self.in_flight.lock().expect("single flight lock poisoned");
+            match in_flight.get(key) {
+                Some(shared_fetch) => shared_fetch.clone(),
+                None => {
+                    let shared_fetch = self.start(key.to_owned(), fetch());
+                    in_flight.insert(key.to_owned(), shared_fetch.clone());
+                    shared_fetch
+                }
+            }
+        };
+
+        shared_fetch.await.map_err(|err| anyhow::Error::msg(format!("{err:#}")))
+    }
+
+    // wraps the request so that it removes itself from the in flight requests once it completes
+    // this happens exactly once, before any of the waiting tasks receive the result
+    fn start<Fut>(&self, key : String, fetch : Fut) -> SharedFetch<V>
+    where
+        Fut : Future<Output = anyhow::Result<V>> + Send + 'static,
+    {
+        let in_flight = Arc::clone(&self.in_flight);
+        async move {
+            let result = fetch.await.map_err(Arc::new);
+            in_flight.lock().expect("single flight lock poisoned").remove(&key);
+            result
+        }
+        .boxed()
+        .shared()
+    }
+}
+
+impl<V : Clone + Send + Sync + 'static> Default for SingleFlight<V> {
+    fn default() -> Self {
+        SingleFlight::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_one_fetch() {
+        let single_flight : Arc<SingleFlight<String>> = Arc::new(SingleFlight::new());
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let tasks : Vec<_> = (0..20).map(|_| {
+            let single_flight = Arc::clone(&single_flight);
+            let fetches = Arc::clone(&fetches);
+            tokio::spawn(async move {
+                single_flight.run("CSC-CTY", || async move {
+                    fetches.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok(String::from("computer science"))
+                }).await
+            })
+        }).collect();
+
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap(), "computer science");
+        }
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // once completed, the next request is sent again
+        let result = single_flight.run("CSC-CTY", || async { Ok(String::from("refreshed")) }).await;
+        assert_eq!(result.unwrap(), "refreshed");
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_shared_and_not_kept() {
+        let single_flight : SingleFlight<u32> = SingleFlight::new();
+        let failing = single_flight.run("0455351", || async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Err(anyhow::Error::msg("connection reset"))
+        });
+        let waiting = single_flight.run("0455351", || async { Ok(1) });
+        let (failing, waiting) = tokio::join!(failing, waiting);
+
+        assert_eq!(failing.unwrap_err().to_string(), "connection reset");
+        assert_eq!(waiting.unwrap_err().to_string(), "connection reset");
+        assert_eq!(single_flight.run("0455351", || async { Ok(2) }).await.unwrap(), 2);
+    }
+}
@@ -0,0 +1,510 @@
+// interactive terminal browser : departments on the left, their courses in the middle and the selected course on the right
+//
+// typing filters the focused list (fuzzy, refer to fuzzy.rs), listings and details are downloaded in the background
+// through a shared CCNYCourseCatalog, so anything browsed before is served from it's caches
+use crate::fuzzy::{fuzzy_filter, FuzzyMatch};
+use anyhow::Result;
+use ccny_course_catalog::{sans_io, CCNYCourseCatalog, CourseDetail, CourseInfo};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+// selections are only downloaded once they stop changing, so that scrolling through a list doesn't download every entry
+const FETCH_DELAY : Duration = Duration::from_millis(150);
+const TICK : Duration = Duration::from_millis(50);
+const PAGE_SIZE : usize = 10;
+
+const FOCUSED_COLOR : Color = Color::Cyan;
+const MATCH_COLOR : Color = Color::Yellow;
+
+/// Runs the browser until it's closed (Esc or Ctrl-C), restoring the terminal afterwards.
+pub async fn run(catalog : CCNYCourseCatalog) -> Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = Browser::new(catalog).run(&mut terminal).await;
+    ratatui::try_restore()?;
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Departments,
+    Courses,
+    Detail,
+}
+
+enum Loadable<T> {
+    Loading,
+    Loaded(T),
+    Failed(String),
+}
+
+// results of the downloads running in the background
+enum Fetched {
+    Courses { department_code : String, courses : Result<Vec<CourseInfo>, String> },
+    Detail { course_group_id : String, detail : Result<Box<CourseDetail>, String> },
+}
+
+// a filterable list, the entries shown are the indices of the matching items (best matches first)
+#[derive(Default)]
+struct FilteredList {
+    filter : String,
+    visible : Vec<(usize, FuzzyMatch)>,
+    state : ListState,
+}
+
+impl FilteredList {
+    fn refilter<'a>(&mut self, labels : impl IntoIterator<Item = &'a str>) {
+        self.visible = fuzzy_filter(&self.filter, labels);
+        self.state.select((!self.visible.is_empty()).then_some(0));
+    }
+
+    fn selected(&self) -> Option<usize> {
+        self.state.selected().and_then(|index| self.visible.get(index)).map(|(item_index, _)| *item_index)
+    }
+
+    // moves the selection by `offset` entries, stopping at either end
+    fn move_selection(&mut self, offset : isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+
+        let selected = self.state.selected().unwrap_or(0) as isize;
+        self.state.select(Some((selected + offset).clamp(0, self.visible.len() as isize - 1) as usize));
+    }
+}
+
+struct Browser {
+    catalog : CCNYCourseCatalog,
+    departments : Vec<(String, String)>,                     // (department code, department name), ordered by name
+    courses : HashMap<String, Loadable<Vec<CourseInfo>>>,    // by department code
+    details : HashMap<String, Loadable<CourseDetail>>,       // by normalized course group id
+    department_list : FilteredList,
+    course_list : FilteredList,
+    focus : Pane,
+    detail_scroll : u16,
+    selection_changed_at : Option<Instant>,
+    sender : UnboundedSender<Fetched>,
+    receiver : UnboundedReceiver<Fetched>,
+    should_quit : bool,
+}
+
+impl Browser {
+    fn new(catalog : CCNYCourseCatalog) -> Browser {
+        let mut departments : Vec<(String, String)> = catalog.get_department_list()
+            .into_iter()
+            .filter_map(|department_name| Some((sans_io::resolve_department_code(&department_name)?, department_name)))
+            .collect();
+        departments.sort_by(|left, right| left.1.cmp(&right.1));
+
+        let (sender, receiver) = unbounded_channel();
+        let mut browser = Browser {
+            catalog,
+            departments,
+            courses : HashMap::new(),
+            details : HashMap::new(),
+            department_list : FilteredList::default(),
+            course_list : FilteredList::default(),
+            focus : Pane::Departments,
+            detail_scroll : 0,
+            selection_changed_at : Some(Instant::now()),
+            sender,
+            receiver,
+            should_quit : false,
+        };
+
+        browser.refilter_departments();
+        browser
+    }
+
+    async fn run(mut self, terminal : &mut DefaultTerminal) -> Result<()> {
+        while !self.should_quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // polling blocks the main task only, downloads keep running on the runtime's worker threads
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key);
+                    }
+                }
+            }
+
+            while let Ok(fetched) = self.receiver.try_recv() {
+                self.receive(fetched);
+            }
+
+            if self.selection_changed_at.is_some_and(|changed_at| changed_at.elapsed() >= FETCH_DELAY) {
+                self.selection_changed_at = None;
+                self.fetch_selection();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key : KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            if key.code == KeyCode::Char('c') {
+                self.should_quit = true;
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => match self.focused_list() {
+                Some(list) if !list.filter.is_empty() => self.edit_filter(String::clear),
+                _ => self.should_quit = true,
+            },
+            KeyCode::Tab | KeyCode::Right | KeyCode::Enter => self.focus = match self.focus {
+                Pane::Departments => Pane::Courses,
+                Pane::Courses | Pane::Detail => Pane::Detail,
+            },
+            KeyCode::BackTab | KeyCode::Left => self.focus = match self.focus {
+                Pane::Departments | Pane::Courses => Pane::Departments,
+                Pane::Detail => Pane::Courses,
+            },
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-(PAGE_SIZE as isize)),
+            KeyCode::PageDown => self.move_selection(PAGE_SIZE as isize),
+            KeyCode::Home => self.move_selection(isize::MIN / 2),
+            KeyCode::End => self.move_selection(isize::MAX / 2),
+            KeyCode::Backspace => self.edit_filter(|filter| { filter.pop(); }),
+            KeyCode::Char(character) => self.edit_filter(|filter| filter.push(character)),
+            _ => {},
+        }
+    }
+
+    fn focused_list(&self) -> Option<&FilteredList> {
+        match self.focus {
+            Pane::Departments => Some(&self.department_list),
+            Pane::Courses => Some(&self.course_list),
+            Pane::Detail => None,
+        }
+    }
+
+    fn move_selection(&mut self, offset : isize) {
+        match self.focus {
+            Pane::Departments => {
+                self.department_list.move_selection(offset);
+                self.department_changed();
+            },
+            Pane::Courses => {
+                self.course_list.move_selection(offset);
+                self.course_changed();
+            },
+            Pane::Detail => self.detail_scroll = (self.detail_scroll as isize + offset).clamp(0, u16::MAX as isize) as u16,
+        }
+    }
+
+    fn edit_filter(&mut self, edit : impl FnOnce(&mut String)) {
+        match self.focus {
+            Pane::Departments => {
+                edit(&mut self.department_list.filter);
+                self.refilter_departments();
+            },
+            Pane::Courses => {
+                edit(&mut self.course_list.filter);
+                self.refilter_courses();
+            },
+            Pane::Detail => {},
+        }
+    }
+
+    fn refilter_departments(&mut self) {
+        let labels : Vec<String> = self.departments.iter().map(|(code, name)| department_label(code, name)).collect();
+        self.department_list.refilter(labels.iter().map(String::as_str));
+        self.department_changed();
+    }
+
+    fn refilter_courses(&mut self) {
+        let labels : Vec<String> = self.selected_courses().unwrap_or_default().iter().map(course_label).collect();
+        self.course_list.refilter(labels.iter().map(String::as_str));
+        self.course_changed();
+    }
+
+    fn department_changed(&mut self) {
+        self.course_list.filter.clear();
+        self.refilter_courses();
+    }
+
+    fn course_changed(&mut self) {
+        self.detail_scroll = 0;
+        self.selection_changed_at = Some(Instant::now());
+    }
+
+    fn selected_department(&self) -> Option<&(String, String)> {
+        self.department_list.selected().map(|index| &self.departments[index])
+    }
+
+    fn selected_courses(&self) -> Option<&[CourseInfo]> {
+        let (department_code, _) = self.selected_department()?;
+        match self.courses.get(department_code)? {
+            Loadable::Loaded(courses) => Some(courses),
+            _ => None,
+        }
+    }
+
+    fn selected_course(&self) -> Option<&CourseInfo> {
+        self.course_list.selected().and_then(|index| self.selected_courses()?.get(index))
+    }
+
+    // downloads the courses of the selected department, or the details of the selected course once it's courses are known
+    fn fetch_selection(&mut self) {
+        let Some((department_code, department_name)) = self.selected_department().cloned() else {
+            return;
+        };
+
+        let mut course_instance = self.catalog.clone();
+        course_instance.set_department_name(&department_name);
+        let sender = self.sender.clone();
+
+        // failed downloads are tried again once they are selected again
+        if needs_fetch(self.courses.get(&department_code)) {
+            self.courses.insert(department_code.clone(), Loadable::Loading);
+            tokio::spawn(async move {
+                let courses = course_instance.get_courses_list().await.map_err(|err| format!("{err:#}"));
+                let _ = sender.send(Fetched::Courses { department_code, courses });
+            });
+            return;
+        }
+
+        let Some(course) = self.selected_course() else {
+            return;
+        };
+
+        // looked up by course group id rather than by name, several courses of a department may share a name
+        let course_group_id = sans_io::normalize_course_group_id(&course.course_group_id);
+        if needs_fetch(self.details.get(&course_group_id)) {
+            self.details.insert(course_group_id.clone(), Loadable::Loading);
+            tokio::spawn(async move {
+                let detail = course_instance.get_course_detail_by_group_id(&course_group_id).await.map(Box::new).map_err(|err| format!("{err:#}"));
+                let _ = sender.send(Fetched::Detail { course_group_id, detail });
+            });
+        }
+    }
+
+    fn receive(&mut self, fetched : Fetched) {
+        match fetched {
+            Fetched::Courses { department_code, courses } => {
+                let is_selected = self.selected_department().is_some_and(|(selected_code, _)| *selected_code == department_code);
+                self.courses.insert(department_code, loadable(courses));
+                if is_selected {
+                    self.refilter_courses();
+                }
+            },
+            Fetched::Detail { course_group_id, detail } => {
+                self.details.insert(course_group_id, loadable(detail.map(|detail| *detail)));
+            },
+        }
+    }
+
+    fn draw(&mut self, frame : &mut Frame) {
+        let [main_area, help_area] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [department_area, course_area, detail_area] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(35),
+            Constraint::Percentage(40),
+        ]).areas(main_area);
+
+        let department_labels : Vec<String> = self.departments.iter().map(|(code, name)| department_label(code, name)).collect();
+        let block = self.block(Pane::Departments, "Departments", &self.department_list, self.departments.len());
+        draw_list(frame, department_area, block, &department_labels, &mut self.department_list);
+
+        let course_labels : Vec<String> = self.selected_courses().unwrap_or_default().iter().map(course_label).collect();
+        let block = self.block(Pane::Courses, "Courses", &self.course_list, course_labels.len());
+        match self.selected_department().and_then(|(department_code, _)| self.courses.get(department_code)) {
+            Some(Loadable::Loaded(_)) => draw_list(frame, course_area, block, &course_labels, &mut self.course_list),
+            Some(Loadable::Failed(err)) => frame.render_widget(Paragraph::new(err.as_str()).red().wrap(Wrap { trim : true }).block(block), course_area),
+            _ => frame.render_widget(Paragraph::new("loading...").dim().block(block), course_area),
+        }
+
+        let block = self.block(Pane::Detail, "Course", &FilteredList::default(), 0);
+        let detail = Paragraph::new(self.detail_lines())
+            .wrap(Wrap { trim : false })
+            .scroll((self.detail_scroll, 0))
+            .block(block);
+        frame.render_widget(detail, detail_area);
+
+        let help = "type to filter · ↑↓ select · ←→/tab switch pane · esc clear filter / quit · ctrl-c quit";
+        frame.render_widget(Line::from(help).dim(), help_area);
+    }
+
+    // bordered block, highlighted while focused, along with the filter and the number of matches
+    fn block(&self, pane : Pane, title : &str, list : &FilteredList, item_count : usize) -> Block<'static> {
+        let mut title = format!(" {title} ");
+        if !list.filter.is_empty() {
+            title.push_str(&format!("/{} ({}/{item_count}) ", list.filter, list.visible.len()));
+        }
+
+        let block = Block::bordered().title(title);
+        if self.focus == pane {
+            block.border_style(Style::new().fg(FOCUSED_COLOR))
+        } else {
+            block
+        }
+    }
+
+    // the listing is shown right away, the details (i.e. the description) are added once downloaded
+    fn detail_lines(&self) -> Vec<Line<'static>> {
+        let Some(course) = self.selected_course() else {
+            return Vec::new();
+        };
+
+        let detail = self.details.get(&sans_io::normalize_course_group_id(&course.course_group_id));
+        let loaded_detail = match detail {
+            Some(Loadable::Loaded(detail)) => Some(detail),
+            _ => None,
+        };
+
+        let long_name = loaded_detail.map_or(course.course_name.as_str(), |detail| detail.long_name.as_str());
+        let mut lines = vec![
+            Line::from(vec![Span::from(course.course_code.clone()).bold().fg(FOCUSED_COLOR), Span::from(format!("  {long_name}")).bold()]),
+            Line::default(),
+        ];
+        lines.extend(field_line("Credits", &course.credits));
+        lines.extend(field_line("Career", &course.career));
+
+        match loaded_detail {
+            Some(detail) => {
+                lines.extend(field_line("Offered", &detail.typically_offered));
+                lines.extend(field_line("Departments", &detail.departments.join(", ")));
+                lines.push(Line::default());
+                lines.push(Line::from("Components").bold());
+                for component in &detail.course_components {
+                    lines.push(Line::from(component_summary(component)));
+                }
+                lines.push(Line::default());
+                lines.push(Line::from(detail.description.clone()));
+            },
+            None => {
+                lines.extend(field_line("Departments", &course.department.join(", ")));
+                lines.push(Line::default());
+                lines.push(Line::from("Components").bold());
+                lines.push(Line::from(component_summary(&course.course_components)));
+                lines.push(Line::default());
+                lines.push(match detail {
+                    Some(Loadable::Failed(err)) => Line::from(format!("failed to download the description : {err}")).red(),
+                    _ => Line::from("loading the description...").dim(),
+                });
+            },
+        }
+
+        lines
+    }
+}
+
+fn needs_fetch<T>(loadable : Option<&Loadable<T>>) -> bool {
+    matches!(loadable, None | Some(Loadable::Failed(_)))
+}
+
+fn loadable<T>(result : Result<T, String>) -> Loadable<T> {
+    match result {
+        Ok(value) => Loadable::Loaded(value),
+        Err(err) => Loadable::Failed(err),
+    }
+}
+
+// i.e. "computer science (CSC-CTY)", filters match against the whole label
+fn department_label(department_code : &str, department_name : &str) -> String {
+    format!("{department_name} ({department_code})")
+}
+
+// i.e. "CSC 21200  Data Structures"
+fn course_label(course : &CourseInfo) -> String {
+    format!("{}  {}", course.course_code, course.course_name)
+}
+
+// fields the catalog left empty are skipped
+fn field_line(name : &str, value : &str) -> Option<Line<'static>> {
+    (!value.is_empty()).then(|| Line::from(vec![Span::from(format!("{name} : ")).dim(), Span::from(value.to_owned())]))
+}
+
+// i.e. "- LEC : Class Meeting, 3 hr./wk., 35 students, In Person"
+fn component_summary(component : &ccny_course_catalog::CourseComponents) -> String {
+    format!(
+        "- {} : {}, {} hr./wk., {} students, {}",
+        component.course_type, component.attendance_type, component.weekly_hours, component.class_size, component.instruction_mode
+    )
+}
+
+fn draw_list(frame : &mut Frame, area : Rect, block : Block, labels : &[String], list : &mut FilteredList) {
+    let items : Vec<ListItem> = list.visible.iter()
+        .map(|(index, fuzzy_match)| ListItem::new(highlight_matches(&labels[*index], &fuzzy_match.positions)))
+        .collect();
+
+    let widget = List::new(items)
+        .block(block)
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(widget, area, &mut list.state);
+}
+
+// characters matched by the filter are colored
+fn highlight_matches(label : &str, positions : &[usize]) -> Line<'static> {
+    let spans : Vec<Span> = label.chars()
+        .enumerate()
+        .map(|(index, character)| match positions.contains(&index) {
+            true => Span::styled(character.to_string(), Style::new().fg(MATCH_COLOR).add_modifier(Modifier::BOLD)),
+            false => Span::raw(character.to_string()),
+        })
+        .collect();
+
+    Line::from(spans)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample_course;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn type_text(browser : &mut Browser, text : &str) {
+        for character in text.chars() {
+            browser.handle_key(KeyEvent::from(KeyCode::Char(character)));
+        }
+    }
+
+    #[test]
+    fn test_filtering_and_rendering() {
+        let mut browser = Browser::new(CCNYCourseCatalog::new(String::new(), None));
+        type_text(&mut browser, "comp sci");
+        assert_eq!(browser.selected_department().unwrap().0, "CSC-CTY");
+
+        // courses are provided directly rather than downloaded
+        let course = sample_course();
+        browser.receive(Fetched::Courses { department_code : String::from("CSC-CTY"), courses : Ok(vec![course]) });
+
+        browser.handle_key(KeyEvent::from(KeyCode::Tab));
+        type_text(&mut browser, "dstr");
+        assert_eq!(browser.selected_course().unwrap().course_code, "CSC 21200");
+        type_text(&mut browser, "x");
+        assert!(browser.selected_course().is_none());
+
+        // esc clears the filter before quitting
+        browser.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert!(browser.selected_course().is_some() && !browser.should_quit);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        let screen : String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("computer science (CSC-CTY)"));
+        assert!(screen.contains("CSC 21200  Data Structures"));
+        assert!(screen.contains("loading the description..."));
+
+        // failed downloads are tried again, rather than showing the failure for good
+        browser.receive(Fetched::Detail { course_group_id : String::from("0455351"), detail : Err(String::from("connection reset")) });
+        assert!(needs_fetch(browser.details.get("0455351")));
+
+        browser.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert!(browser.should_quit);
+    }
+}
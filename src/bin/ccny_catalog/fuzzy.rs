@@ -0,0 +1,121 @@
+// fuzzy matching of filters against names, i.e. "dsa" matches "Data Structures and Algorithms"
+//
+// the characters of the query have to appear in order (case insensitive, whitespace within the query is ignored),
+// matches at the start of words and runs of consecutive characters score higher than scattered ones
+use std::cmp::Reverse;
+
+const WORD_START_BONUS : i64 = 8;
+const CONSECUTIVE_BONUS : i64 = 5;
+const MAX_GAP_PENALTY : i64 = 3;        // per gap between matched characters, so that long names aren't punished too much
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score : i64,
+    pub positions : Vec<usize>,         // indices of the matched characters (not bytes) within the candidate
+}
+
+pub fn fuzzy_match(query : &str, candidate : &str) -> Option<FuzzyMatch> {
+    let query : Vec<char> = query.chars().filter(|character| !character.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch { score : 0, positions : Vec::new() });
+    }
+
+    let candidate : Vec<char> = candidate.chars().collect();
+    let lowercase : Vec<char> = candidate.iter().map(|character| character.to_lowercase().next().unwrap_or(*character)).collect();
+
+    // every occurrence of the first character is tried, i.e. "st" should match the start of "Structures" within "Data Structures"
+    // rather than the 's' ending "Data Structures" being reached through the first 's'
+    let mut best_match : Option<FuzzyMatch> = None;
+    for start in (0..candidate.len()).filter(|&start| lowercase[start] == query[0]) {
+        let Some(positions) = match_positions(&query, &lowercase, start) else {
+            break;      // later starts can't match either, since fewer characters remain
+        };
+
+        let score = score(&candidate, &positions);
+        if best_match.as_ref().is_none_or(|best_match| score > best_match.score) {
+            best_match = Some(FuzzyMatch { score, positions });
+        }
+    }
+
+    best_match
+}
+
+/// Indices of the candidates matching the query along with their match, best matches first (ties keep their original order).
+pub fn fuzzy_filter<'a>(query : &str, candidates : impl IntoIterator<Item = &'a str>) -> Vec<(usize, FuzzyMatch)> {
+    let mut matches : Vec<(usize, FuzzyMatch)> = candidates.into_iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| Some((index, fuzzy_match(query, candidate)?)))
+        .collect();
+
+    matches.sort_by_key(|(_, fuzzy_match)| Reverse(fuzzy_match.score));
+    matches
+}
+
+// the closest following occurrence of every remaining character
+fn match_positions(query : &[char], lowercase : &[char], start : usize) -> Option<Vec<usize>> {
+    let mut positions = vec![start];
+    let mut position = start;
+    for character in &query[1..] {
+        position += 1 + lowercase[position + 1..].iter().position(|candidate_character| candidate_character == character)?;
+        positions.push(position);
+    }
+
+    Some(positions)
+}
+
+fn score(candidate : &[char], positions : &[usize]) -> i64 {
+    let mut score = 0;
+    for (index, &position) in positions.iter().enumerate() {
+        score += 1;
+        if is_word_start(candidate, position) {
+            score += WORD_START_BONUS;
+        }
+
+        if index > 0 {
+            match position - positions[index - 1] - 1 {
+                0 => score += CONSECUTIVE_BONUS,
+                gap => score -= (gap as i64).min(MAX_GAP_PENALTY),
+            }
+        }
+    }
+
+    score
+}
+
+// i.e. the 'S' of "Data Structures" or of "DataStructures"
+fn is_word_start(candidate : &[char], position : usize) -> bool {
+    let Some(&previous) = position.checked_sub(1).map(|index| &candidate[index]) else {
+        return true;
+    };
+
+    !previous.is_alphanumeric() || (previous.is_lowercase() && candidate[position].is_uppercase())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        let data_structures = fuzzy_match("dsa", "Data Structures and Algorithms").unwrap();
+        assert_eq!(data_structures.positions, vec![0, 5, 16]);
+        assert!(fuzzy_match("algo data", "Data Structures and Algorithms").is_none());
+        assert!(fuzzy_match("", "Data Structures").is_some_and(|empty_match| empty_match.positions.is_empty()));
+
+        // the start of "Structures" is preferred over the first 's' found
+        assert_eq!(fuzzy_match("str", "Systems Structures").unwrap().positions, vec![8, 9, 10]);
+        assert_eq!(fuzzy_match("csc 212", "CSC 21200  Data Structures").unwrap().positions, vec![0, 1, 2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_fuzzy_filter_orders_by_score() {
+        let candidates = ["computer engineering", "civil engineering", "computer science", "chemistry"];
+        let matches : Vec<usize> = fuzzy_filter("comp sci", candidates).into_iter().map(|(index, _)| index).collect();
+        assert_eq!(matches, vec![2]);
+
+        let matches : Vec<usize> = fuzzy_filter("ce", candidates).into_iter().map(|(index, _)| index).collect();
+        assert_eq!(matches[0], 1);
+        assert_eq!(fuzzy_filter("", candidates).len(), candidates.len());
+    }
+}
@@ -0,0 +1,458 @@
+// ccny-catalog : command line interface to CUNY City College's course catalog
+//
+// i.e.
+//   ccny-catalog departments
+//   ccny-catalog courses "computer science" --format csv
+//   ccny-catalog course "computer science" "CSC 21200"
+//   ccny-catalog search "data structures"
+//   ccny-catalog export sqlite catalog.db --details
+//   ccny-catalog terms --format json
+//   ccny-catalog browse
+//
+// responses are cached on disk between runs (see --cache-dir and --no-cache)
+// exports of the whole catalog download every response again, bypassing the cache
+mod browser;
+mod fuzzy;
+mod output;
+
+use anyhow::{Context, Result};
+use ccny_course_catalog::{
+    sans_io, write_model_schemas, CCNYCourseCatalog, CatalogSnapshot, CourseDetail, CourseDetailColumn, CourseInfo, CourseJsonLd,
+    CsvExporter, DiskCacheConfig, NdjsonCompression, NdjsonWriter, SiteFormat, SiteGenerator, SqliteExporter,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures::stream::{self, StreamExt};
+use output::OutputFormat;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWrite;
+
+// number of requests sent at the same time while searching every department or downloading course details
+const CONCURRENT_REQUESTS : usize = 4;
+
+#[derive(Parser, Debug)]
+#[command(name = "ccny-catalog", version, about = "Browse and export CUNY City College's course catalog")]
+struct Cli {
+    #[command(flatten)]
+    options : GlobalOptions,
+
+    #[command(subcommand)]
+    command : Command,
+}
+
+#[derive(Args, Debug)]
+struct GlobalOptions {
+    /// Output format of departments, courses and terms
+    #[arg(long, short, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    format : OutputFormat,
+
+    /// Directory responses are cached in between runs [default : ~/.cache/ccny-catalog]
+    #[arg(long, global = true)]
+    cache_dir : Option<PathBuf>,
+
+    /// Always download responses, without reading or writing the on-disk cache
+    #[arg(long, global = true)]
+    no_cache : bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Lists every department
+    Departments,
+
+    /// Lists the courses of a department
+    Courses {
+        /// Name of the department (i.e. "computer science"), close matches are accepted
+        department : String,
+    },
+
+    /// Shows the details of a course
+    Course {
+        /// Name of the department (i.e. "computer science"), close matches are accepted
+        department : String,
+
+        /// Course code (i.e. "CSC 21200") or name (i.e. "data structures") of the course
+        course : String,
+    },
+
+    /// Searches courses by name or course code
+    Search {
+        /// Part of the name or course code of the course
+        query : String,
+
+        /// Only searches this department, every department is searched otherwise
+        #[arg(long, short)]
+        department : Option<String>,
+    },
+
+    /// Exports the catalog
+    Export(ExportArgs),
+
+    /// Lists the academic terms
+    Terms,
+
+    /// Browses departments and courses interactively, with fuzzy filtering
+    Browse,
+}
+
+#[derive(Args, Debug)]
+struct ExportArgs {
+    #[arg(value_enum)]
+    kind : ExportKind,
+
+    /// File (or directory for site and schemas) to write into, written to stdout when omitted (csv, ndjson, snapshot, json-ld)
+    output : Option<PathBuf>,
+
+    /// Only exports the courses of this department (csv), downloaded through the on-disk cache
+    #[arg(long, short)]
+    department : Option<String>,
+
+    /// Also downloads the details of every course, which requires one request per course
+    #[arg(long)]
+    details : bool,
+
+    /// Reads the catalog from a snapshot (written by `export snapshot`) instead of downloading it.
+    /// Downloads of the whole catalog bypass the on-disk cache
+    #[arg(long)]
+    snapshot : Option<PathBuf>,
+
+    /// Renders the site as markdown rather than html
+    #[arg(long)]
+    markdown : bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportKind {
+    /// Course listings (or details with --details) as csv
+    Csv,
+    /// Course listings (or details with --details) as newline delimited json, gzip compressed if the output ends with .gz
+    Ndjson,
+    /// Normalized sqlite database, updated if it already exists
+    Sqlite,
+    /// Versioned json snapshot, readable with --snapshot
+    Snapshot,
+    /// Static html (or markdown) site with a page per department and per course
+    Site,
+    /// schema.org Course markup of every course
+    JsonLd,
+    /// JSON Schema of every model
+    Schemas,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    match run(Cli::parse()).await {
+        // the output was piped into a command that stopped reading (i.e. head), which isn't a failure
+        Err(err) if err.downcast_ref::<std::io::Error>().is_some_and(|err| err.kind() == std::io::ErrorKind::BrokenPipe) => Ok(()),
+        result => result,
+    }
+}
+
+async fn run(cli : Cli) -> Result<()> {
+    let options = &cli.options;
+    let format = options.format;
+
+    match cli.command {
+        Command::Departments => {
+            let mut departments : Vec<(String, String)> = catalog(options, "")
+                .get_department_list()
+                .into_iter()
+                .filter_map(|department_name| Some((sans_io::resolve_department_code(&department_name)?, department_name)))
+                .collect();
+            departments.sort();
+            output::print_departments(&departments, format)
+        },
+        Command::Courses { department } => {
+            let courses = catalog(options, &department).get_courses_list().await?;
+            output::print_courses(&courses, format)
+        },
+        Command::Course { department, course } => {
+            let mut course_instance = catalog(options, &department);
+            let courses = course_instance.get_courses_list().await?;
+
+            // course codes are matched exactly (by course group id, since several courses may share a name), names are matched to the closest one
+            let course_detail = match find_by_course_code(&courses, &course) {
+                Some(course) => course_instance.get_course_detail_by_group_id(&course.course_group_id).await?,
+                None => {
+                    course_instance.set_course_name(&course);
+                    course_instance.get_course_detail().await?
+                },
+            };
+            output::print_course_detail(&course_detail, format)
+        },
+        Command::Search { query, department } => {
+            let courses = search(options, &query, department).await?;
+            output::print_courses(&courses, format)
+        },
+        Command::Export(args) => export(options, args).await,
+        Command::Terms => {
+            let terms = catalog(options, "").get_terms().await?;
+            output::print_terms(&terms, format)
+        },
+        Command::Browse => browser::run(catalog(options, "")).await,
+    }
+}
+
+fn catalog(options : &GlobalOptions, department_name : &str) -> CCNYCourseCatalog {
+    let mut course_instance = CCNYCourseCatalog::new(department_name.to_owned(), None);
+    if !options.no_cache {
+        let directory = options.cache_dir.clone().unwrap_or_else(default_cache_directory);
+        course_instance.set_disk_cache(Some(DiskCacheConfig::new(directory)));
+    }
+
+    course_instance
+}
+
+// $XDG_CACHE_HOME/ccny-catalog, falling back to ~/.cache/ccny-catalog
+fn default_cache_directory() -> PathBuf {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("ccny-catalog")
+}
+
+// i.e. "csc21200" matches "CSC 21200"
+fn find_by_course_code<'a>(courses : &'a [CourseInfo], course_code : &str) -> Option<&'a CourseInfo> {
+    let normalize = |course_code : &str| course_code.split_whitespace().collect::<String>().to_uppercase();
+    let course_code = normalize(course_code);
+    courses.iter().find(|course| normalize(&course.course_code) == course_code)
+}
+
+// courses whose name or course code contains the query, ordered by course code
+async fn search(options : &GlobalOptions, query : &str, department : Option<String>) -> Result<Vec<CourseInfo>> {
+    let department_names = match department {
+        Some(department_name) => vec![department_name],
+        None => catalog(options, "").get_department_list(),
+    };
+
+    let query = query.to_lowercase();
+    let compact_query : String = query.split_whitespace().collect();
+    let mut matches : BTreeMap<(String, String), CourseInfo> = BTreeMap::new();
+
+    let mut listings = stream::iter(department_names)
+        .map(|department_name| async move {
+            let courses = catalog(options, &department_name).get_courses_list().await;
+            (department_name, courses)
+        })
+        .buffer_unordered(CONCURRENT_REQUESTS);
+
+    while let Some((department_name, courses)) = listings.next().await {
+        // a single department failing shouldn't hide the matches within the others
+        let courses = match courses {
+            Ok(courses) => courses,
+            Err(err) => {
+                eprintln!("skipping {department_name} : {err:#}");
+                continue;
+            },
+        };
+
+        for course in courses {
+            let course_code : String = course.course_code.to_lowercase().split_whitespace().collect();
+            if course.course_name.to_lowercase().contains(&query) || course_code.contains(&compact_query) {
+                let key = (course.course_code.clone(), sans_io::normalize_course_group_id(&course.course_group_id));
+                matches.entry(key).or_insert(course);
+            }
+        }
+    }
+
+    Ok(matches.into_values().collect())
+}
+
+async fn export(options : &GlobalOptions, args : ExportArgs) -> Result<()> {
+    match args.kind {
+        ExportKind::Csv => {
+            let writer = output_writer(args.output.as_deref())?;
+            // a single department is downloaded through the catalog (and it's caches) rather than as a whole snapshot
+            if let (Some(department_name), None) = (&args.department, &args.snapshot) {
+                let course_instance = catalog(options, department_name);
+                let courses = course_instance.get_courses_list().await?;
+                if !args.details {
+                    return CsvExporter::new().write(&courses, writer);
+                }
+
+                let course_details = course_details(&course_instance, &courses).await;
+                return CsvExporter::<CourseDetailColumn>::new().write(&course_details, writer);
+            }
+
+            let snapshot = snapshot(&args).await?;
+            let department_code = args.department.as_deref()
+                .map(|department_name| sans_io::resolve_department_code(department_name).context("unknown department"))
+                .transpose()?;
+            let in_department = |departments : &[String]| department_code.as_ref().is_none_or(|code| departments.contains(code));
+
+            if args.details {
+                let course_details : Vec<_> = snapshot.course_details.into_values().filter(|detail| in_department(&detail.departments)).collect();
+                CsvExporter::<CourseDetailColumn>::new().write(&course_details, writer)
+            } else {
+                let courses : Vec<CourseInfo> = unique_courses(&snapshot).into_iter().filter(|course| in_department(&course.department)).cloned().collect();
+                CsvExporter::new().write(&courses, writer)
+            }
+        },
+        ExportKind::Ndjson => match &args.output {
+            Some(path) => export_ndjson(NdjsonWriter::create(path).await?, &args).await,
+            None => export_ndjson(NdjsonWriter::new(tokio::io::stdout(), NdjsonCompression::None), &args).await,
+        },
+        ExportKind::Sqlite => {
+            let path = args.output.as_deref().context("the sqlite export requires an output file")?;
+            let snapshot = snapshot(&args).await?;
+            SqliteExporter::open(path)?.export_snapshot(&snapshot)?;
+            eprintln!("exported {} courses into {}", unique_courses(&snapshot).len(), path.display());
+            Ok(())
+        },
+        ExportKind::Snapshot => {
+            let snapshot = snapshot(&args).await?;
+            match &args.output {
+                Some(path) => snapshot.save(path),
+                None => output::print_json(&snapshot),
+            }
+        },
+        ExportKind::Site => {
+            let directory = args.output.as_deref().context("the site export requires an output directory")?;
+            let format = if args.markdown { SiteFormat::Markdown } else { SiteFormat::Html };
+            let snapshot = snapshot(&args).await?;
+            let page_count = SiteGenerator::new(&snapshot).write(directory, format)?;
+            eprintln!("wrote {page_count} pages into {}", directory.display());
+            Ok(())
+        },
+        ExportKind::JsonLd => {
+            let snapshot = snapshot(&args).await?;
+            let mut writer = output_writer(args.output.as_deref())?;
+            serde_json::to_writer_pretty(&mut writer, &CourseJsonLd::for_snapshot(&snapshot))?;
+            writeln!(writer)?;
+            Ok(())
+        },
+        ExportKind::Schemas => {
+            let directory = args.output.as_deref().context("the schemas export requires an output directory")?;
+            write_model_schemas(directory)
+        },
+    }
+}
+
+// the snapshot passed with --snapshot, otherwise the catalog is downloaded
+async fn snapshot(args : &ExportArgs) -> Result<CatalogSnapshot> {
+    if let Some(path) = &args.snapshot {
+        return CatalogSnapshot::load(path);
+    }
+
+    eprintln!("downloading the catalog{}...", if args.details { " along with the details of every course" } else { "" });
+    let snapshot = CatalogSnapshot::fetch(args.details).await?;
+    for (department_code, err) in &snapshot.failed_departments {
+        eprintln!("skipping {department_code} : {err}");
+    }
+    for (course_group_id, err) in &snapshot.failed_course_details {
+        eprintln!("skipping the details of {course_group_id} : {err}");
+    }
+    if let Some(err) = &snapshot.failed_terms {
+        eprintln!("skipping the terms : {err}");
+    }
+
+    Ok(snapshot)
+}
+
+// details of the provided courses, ordered by course group id
+// courses whose details fail to download are skipped, the same way as departments within snapshots
+async fn course_details(course_instance : &CCNYCourseCatalog, courses : &[CourseInfo]) -> Vec<CourseDetail> {
+    let course_group_ids : BTreeSet<String> = courses.iter().map(|course| sans_io::normalize_course_group_id(&course.course_group_id)).collect();
+
+    let mut details = stream::iter(course_group_ids)
+        .map(|course_group_id| async move {
+            let course_detail = course_instance.get_course_detail_by_group_id(&course_group_id).await;
+            (course_group_id, course_detail)
+        })
+        .buffered(CONCURRENT_REQUESTS);
+
+    let mut course_details = Vec::new();
+    while let Some((course_group_id, course_detail)) = details.next().await {
+        match course_detail {
+            Ok(course_detail) => course_details.push(course_detail),
+            Err(err) => eprintln!("skipping the details of {course_group_id} : {err:#}"),
+        }
+    }
+
+    course_details
+}
+
+// records are written as they are downloaded, unless they are read from a snapshot
+async fn export_ndjson<W : AsyncWrite + Unpin>(mut writer : NdjsonWriter<W>, args : &ExportArgs) -> Result<()> {
+    match (&args.snapshot, args.details) {
+        (Some(_), true) => writer.write_records(snapshot(args).await?.course_details.values()).await?,
+        (Some(_), false) => writer.write_records(unique_courses(&snapshot(args).await?)).await?,
+        (None, true) => { writer.write_all_course_details().await?; },
+        (None, false) => { writer.write_all_courses().await?; },
+    }
+
+    eprintln!("exported {} records", writer.records_written());
+    writer.finish().await?;
+    Ok(())
+}
+
+// courses listed under several departments are only kept once, ordered by course group id
+fn unique_courses(snapshot : &CatalogSnapshot) -> Vec<&CourseInfo> {
+    let mut courses = BTreeMap::new();
+    for course in snapshot.courses() {
+        courses.entry(sans_io::normalize_course_group_id(&course.course_group_id)).or_insert(course);
+    }
+
+    courses.into_values().collect()
+}
+
+fn output_writer(path : Option<&Path>) -> Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))?),
+        None => Box::new(std::io::stdout().lock()),
+    })
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    // listing of CSC 21200 Data Structures, shared by the tests of every module of the binary
+    pub(crate) fn sample_course() -> CourseInfo {
+        serde_json::from_value(serde_json::json!({
+            "unique_id" : "0455351-1901-01-01",
+            "course_name" : "Data Structures",
+            "career" : "Undergraduate",
+            "course_code" : "CSC 21200",
+            "course_components" : {
+                "course_type" : "LEC",
+                "attendance_type" : "Class Meeting",
+                "weekly_hours" : 4,
+                "class_size" : 35,
+                "final_exam" : "Yes",
+                "exam_seat_spacing" : 1,
+                "instruction_mode" : "In Person"
+            },
+            "effective_start_date" : "1901-01-01",
+            "effective_end_date" : "unknown",
+            "course_group_id" : "0455351",
+            "course_number" : 21200,
+            "department" : ["CSC-CTY"],
+            "subject_code" : "CSC",
+            "credits" : "3"
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_arguments() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["ccny-catalog", "course", "computer science", "CSC 21200", "--format", "json", "--no-cache"]).unwrap();
+        assert!(matches!(cli.command, Command::Course { ref course, .. } if course == "CSC 21200"));
+        assert_eq!(cli.options.format, OutputFormat::Json);
+        assert!(cli.options.no_cache);
+
+        assert!(Cli::try_parse_from(["ccny-catalog", "export", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_find_by_course_code() {
+        let courses = vec![sample_course()];
+        assert_eq!(find_by_course_code(&courses, "csc21200").unwrap().course_name, "Data Structures");
+        assert!(find_by_course_code(&courses, "data structures").is_none());
+    }
+}
@@ -0,0 +1,162 @@
+// rendering of command results as aligned tables, json or csv
+use anyhow::Result;
+use ccny_course_catalog::{CourseDetail, CourseInfo, CsvExporter, Term};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned columns, for reading within a terminal
+    Table,
+    /// Pretty printed json
+    Json,
+    /// Comma separated values, with a header row
+    Csv,
+}
+
+pub fn print_departments(departments : &[(String, String)], format : OutputFormat) -> Result<()> {
+    let rows : Vec<Vec<String>> = departments.iter()
+        .map(|(department_code, department_name)| vec![department_code.clone(), department_name.clone()])
+        .collect();
+
+    match format {
+        OutputFormat::Json => {
+            let departments : Vec<_> = departments.iter()
+                .map(|(code, name)| serde_json::json!({ "code" : code, "name" : name }))
+                .collect();
+            print_json(&departments)
+        },
+        _ => print_rows(&["code", "name"], &rows, format),
+    }
+}
+
+pub fn print_courses(courses : &[CourseInfo], format : OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            let rows : Vec<Vec<String>> = courses.iter()
+                .map(|course| vec![course.course_code.clone(), course.course_name.clone(), course.credits.clone(), course.course_components.course_type.clone()])
+                .collect();
+            print_rows(&["code", "name", "credits", "type"], &rows, format)
+        },
+        OutputFormat::Json => print_json(&courses),
+        OutputFormat::Csv => CsvExporter::new().write(courses, std::io::stdout().lock()),
+    }
+}
+
+pub fn print_course_detail(detail : &CourseDetail, format : OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            let components = detail.course_components.iter()
+                .map(|component| format!("{} ({} hr./wk., {})", component.course_type, component.weekly_hours, component.instruction_mode))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let rows : Vec<Vec<String>> = [
+                ("code", detail.course_code.as_str()),
+                ("name", detail.long_name.as_str()),
+                ("credits", detail.credits.as_str()),
+                ("components", components.as_str()),
+                ("career", detail.career.as_str()),
+                ("college", detail.college.as_str()),
+                ("offered", detail.typically_offered.as_str()),
+                ("departments", &detail.departments.join(", ")),
+            ]
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(field, value)| vec![field.to_owned(), value.to_owned()])
+            .collect();
+
+            let mut stdout = std::io::stdout().lock();
+            write_table(&mut stdout, &[], &rows)?;
+            if !detail.description.is_empty() {
+                writeln!(stdout, "\n{}", detail.description)?;
+            }
+            Ok(())
+        },
+        OutputFormat::Json => print_json(detail),
+        OutputFormat::Csv => CsvExporter::new().write(std::slice::from_ref(detail), std::io::stdout().lock()),
+    }
+}
+
+pub fn print_terms(terms : &[Term], format : OutputFormat) -> Result<()> {
+    let rows : Vec<Vec<String>> = terms.iter()
+        .map(|term| vec![term.id.clone(), term.name.clone(), term.start_date.clone(), term.end_date.clone()])
+        .collect();
+
+    match format {
+        OutputFormat::Json => print_json(&terms),
+        _ => print_rows(&["id", "name", "start date", "end date"], &rows, format),
+    }
+}
+
+// written through the lock rather than println!, which panics once stdout is closed (i.e. piped into head)
+pub fn print_json<T : Serialize + ?Sized>(value : &T) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, value)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+fn print_rows(header : &[&str], rows : &[Vec<String>], format : OutputFormat) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    if format == OutputFormat::Csv {
+        let mut writer = csv::Writer::from_writer(stdout);
+        writer.write_record(header)?;
+        for row in rows {
+            writer.write_record(row)?;
+        }
+        writer.flush()?;
+        return Ok(());
+    }
+
+    write_table(&mut stdout, header, rows)
+}
+
+// columns are padded to their widest value, the last column isn't padded so that long names don't leave trailing whitespace
+pub fn write_table(writer : &mut impl Write, header : &[&str], rows : &[Vec<String>]) -> Result<()> {
+    let header : Vec<String> = header.iter().map(|name| name.to_uppercase()).collect();
+    let column_count = rows.iter().map(Vec::len).chain([header.len()]).max().unwrap_or(0);
+    let mut widths = vec![0; column_count];
+    for row in rows.iter().chain((!header.is_empty()).then_some(&header)) {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+
+    for row in (!header.is_empty()).then_some(&header).into_iter().chain(rows) {
+        let mut line = String::new();
+        for (index, value) in row.iter().enumerate() {
+            if index + 1 == row.len() {
+                line.push_str(value);
+            } else {
+                line.push_str(&format!("{value:<width$}  ", width = widths[index]));
+            }
+        }
+        writeln!(writer, "{line}")?;
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_columns_are_aligned() {
+        let rows = vec![
+            vec![String::from("CSC 10300"), String::from("Introduction to Computing"), String::from("3")],
+            vec![String::from("CSC 21200"), String::from("Data Structures"), String::from("4")],
+        ];
+
+        let mut output = Vec::new();
+        write_table(&mut output, &["code", "name", "credits"], &rows).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\
+CODE       NAME                       CREDITS
+CSC 10300  Introduction to Computing  3
+CSC 21200  Data Structures            4
+");
+    }
+}
@@ -0,0 +1,327 @@
+// Local stand-in for the Coursedog API
+// serves the same endpoints the library talks to, but from a directory of JSON snapshots
+// this allows the real request building logic to be exercised without any network access
+//
+// Usage:
+// cargo run --bin coursedog-mock -- <snapshot directory> [address]
+//
+// Snapshot directory layout:
+// - terms.json : served as is for `/general/terms`
+// - every other *.json file : either an array of course objects or an object with a "data" array
+//   (i.e. a response body saved from the Coursedog API)
+//
+// point the library at the server by setting CCNY_COURSEDOG_BASE_URL to the printed address
+// (or per instance, through CCNYCourseCatalog::set_base_url)
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+const DEFAULT_ADDRESS : &str = "127.0.0.1:8787";
+const COURSE_SEARCH_PATH : &str = "/courses/search/$filters";
+const TERMS_PATH : &str = "/general/terms";
+
+// in-memory copy of the snapshot directory
+// loaded once on startup and shared between connections
+struct Snapshots {
+    courses : Vec<Value>,
+    terms : Value,
+}
+
+impl Snapshots {
+    fn load(directory : &Path) -> Result<Snapshots> {
+        let mut snapshots = Snapshots {
+            courses : Vec::new(),
+            terms : json!([]),
+        };
+
+        let mut paths : Vec<PathBuf> = std::fs::read_dir(directory)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .collect();
+        paths.sort();       // keeps the order of the courses stable between runs
+
+        for path in paths {
+            let contents : Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            if path.file_stem().is_some_and(|stem| stem == "terms") {
+                snapshots.terms = contents;
+                continue;
+            }
+
+            // accept both raw arrays and saved api responses
+            match contents {
+                Value::Array(courses) => snapshots.courses.extend(courses),
+                Value::Object(mut response) => {
+                    if let Some(Value::Array(courses)) = response.remove("data") {
+                        snapshots.courses.extend(courses);
+                    }
+                }
+                _ => eprintln!("skipping {path:?}, expected an array of courses"),
+            }
+        }
+
+        Ok(snapshots)
+    }
+}
+
+// the parts of an incoming request that the stand-in cares about
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    method : String,
+    path : String,
+    query : Vec<(String, String)>,
+    body : Value,
+}
+
+impl Request {
+    fn query_param(&self, name : &str) -> Option<&str> {
+        self.query.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let snapshot_directory = PathBuf::from(args.next().unwrap_or(String::from("snapshots")));
+    let address = args.next().unwrap_or(String::from(DEFAULT_ADDRESS));
+
+    let snapshots = Arc::new(Snapshots::load(&snapshot_directory)?);
+    let listener = TcpListener::bind(&address).await?;
+    println!("serving {} courses from {snapshot_directory:?}", snapshots.courses.len());
+    println!("export CCNY_COURSEDOG_BASE_URL=http://{}", listener.local_addr()?);
+
+    serve(listener, snapshots).await
+}
+
+// accepts connections until the process is terminated
+async fn serve(listener : TcpListener, snapshots : Arc<Snapshots>) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let snapshots = Arc::clone(&snapshots);
+        tokio::spawn(async move {
+            if let Err(error) = handle_connection(stream, &snapshots).await {
+                eprintln!("failed to handle request : {error}");
+            }
+        });
+    }
+}
+
+// every connection serves a single request and is then closed
+async fn handle_connection(stream : TcpStream, snapshots : &Snapshots) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let request = read_request(&mut reader).await?;
+    let (status, body) = route(&request, snapshots);
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    reader.get_mut().write_all(response.as_bytes()).await?;
+    reader.get_mut().shutdown().await?;
+    Ok(())
+}
+
+async fn read_request(reader : &mut BufReader<TcpStream>) -> Result<Request> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let target = parts.next().unwrap_or("/").to_owned();
+
+    // only the content length is needed out of the headers
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    parse_request(&method, &target, &body)
+}
+
+fn parse_request(method : &str, target : &str, body : &[u8]) -> Result<Request> {
+    // the host is irrelevant, it's only needed so that the target can be parsed as an url
+    let url = reqwest::Url::parse(&format!("http://localhost{target}"))?;
+    let path = percent_decode(url.path());
+    let body = if body.is_empty() { Value::Null } else { serde_json::from_slice(body)? };
+
+    Ok(Request {
+        method : method.to_owned(),
+        path,
+        query : url.query_pairs().map(|(key, value)| (key.into_owned(), value.into_owned())).collect(),
+        body,
+    })
+}
+
+// the library requests `%24filters` for course listings and `$filters` for course details
+fn percent_decode(path : &str) -> String {
+    path.replace("%24", "$")
+}
+
+fn route(request : &Request, snapshots : &Snapshots) -> (&'static str, Value) {
+    if request.method == "POST" && request.path.ends_with(COURSE_SEARCH_PATH) {
+        return ("200 OK", search_courses(request, snapshots));
+    }
+
+    if request.method == "GET" && request.path.ends_with(TERMS_PATH) {
+        return ("200 OK", snapshots.terms.clone());
+    }
+
+    ("404 Not Found", json!({ "error" : format!("no stand-in for {} {}", request.method, request.path) }))
+}
+
+// mirrors the subset of the search endpoint the library relies on
+// - the "departments" filter within the payload
+// - the courseGroupIds query parameter
+// - skip and limit (a limit of 0 returns everything)
+fn search_courses(request : &Request, snapshots : &Snapshots) -> Value {
+    let department_codes = requested_departments(&request.body);
+    let course_group_ids : Vec<&str> = request
+        .query_param("courseGroupIds")
+        .map(|ids| ids.split(',').filter(|id| !id.is_empty()).collect())
+        .unwrap_or_default();
+    let skip : usize = request.query_param("skip").and_then(|skip| skip.parse().ok()).unwrap_or(0);
+    let limit : usize = request.query_param("limit").and_then(|limit| limit.parse().ok()).unwrap_or(0);
+
+    let matching_courses : Vec<&Value> = snapshots
+        .courses
+        .iter()
+        .filter(|course| department_codes.is_empty() || department_codes.iter().any(|code| belongs_to_department(course, code)))
+        .filter(|course| course_group_ids.is_empty() || course_group_ids.iter().any(|id| course["courseGroupId"] == *id))
+        .collect();
+
+    let list_length = matching_courses.len();
+    let page : Vec<&Value> = matching_courses
+        .into_iter()
+        .skip(skip)
+        .take(if limit == 0 { usize::MAX } else { limit })
+        .collect();
+
+    json!({
+        "data" : page,
+        "limit" : limit,
+        "listLength" : list_length,
+        "skip" : skip,
+    })
+}
+
+// collects the department codes out of the filter payload sent by fetch_courses_by_department_helper
+// filter groups are nested, so the search is recursive
+fn requested_departments(filters : &Value) -> Vec<String> {
+    let mut department_codes = Vec::new();
+    if filters["name"] == "departments" {
+        if let Some(values) = filters["value"].as_array() {
+            department_codes.extend(values.iter().filter_map(|value| value.as_str().map(String::from)));
+        }
+    }
+
+    if let Some(nested_filters) = filters["filters"].as_array() {
+        for nested in nested_filters {
+            department_codes.extend(requested_departments(nested));
+        }
+    }
+
+    department_codes
+}
+
+// listings store departments as plain ids, course details store them as objects
+fn belongs_to_department(course : &Value, department_code : &str) -> bool {
+    course["departments"]
+        .as_array()
+        .is_some_and(|departments| departments.iter().any(|department| department == department_code || department["id"] == department_code))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_course(course_group_id : &str, code : &str, name : &str, department : &str) -> Value {
+        json!({
+            "_id" : format!("{course_group_id}-1901-01-01"),
+            "name" : name,
+            "career" : "Undergraduate",
+            "code" : code,
+            "components" : [{
+                "code" : "LEC",
+                "contactHours" : 3,
+                "defaultSectionSize" : 35,
+                "finalExamType" : "Yes",
+                "attendanceType" : "Class Meeting",
+                "examSeatSpacing" : 1,
+                "instructionMode" : "In Person"
+            }],
+            "effectiveStartDate" : "1901-01-01",
+            "effectiveEndDate" : null,
+            "courseGroupId" : course_group_id,
+            "courseNumber" : code.split_whitespace().last().unwrap(),
+            "departments" : [department],
+            "subjectCode" : code.split_whitespace().next().unwrap(),
+            "credits" : { "creditHours" : { "max" : 3, "min" : 3 } }
+        })
+    }
+
+    fn sample_snapshots() -> Snapshots {
+        Snapshots {
+            courses : vec![
+                sample_course("0455351", "CSC 21200", "Data Structures", "CSC-CTY"),
+                sample_course("0455781", "CSC 44200", "Systems Simulation", "CSC-CTY"),
+                sample_course("0460001", "EE 21000", "Switching Systems", "EE-CTY"),
+            ],
+            terms : json!([{ "id" : "1249", "name" : "2024 Fall Term" }]),
+        }
+    }
+
+    fn department_payload(department_code : &str) -> Value {
+        json!({
+            "condition" : "AND",
+            "filters" : [
+                { "condition" : "and", "filters" : [{ "name" : "status", "value" : "Active" }] },
+                { "name" : "departments", "type" : "contains", "value" : [department_code] }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_department_filter_and_pagination() {
+        let snapshots = sample_snapshots();
+        let request = parse_request(
+            "POST",
+            "/cm/cty01/courses/search/%24filters?skip=1&limit=1",
+            department_payload("CSC-CTY").to_string().as_bytes(),
+        ).unwrap();
+
+        let response = search_courses(&request, &snapshots);
+        assert_eq!(response["listLength"], 2);
+        assert_eq!(response["data"].as_array().unwrap().len(), 1);
+        assert_eq!(response["data"][0]["code"], "CSC 44200");
+    }
+
+    #[test]
+    fn test_course_group_id_filter() {
+        let snapshots = sample_snapshots();
+        let request = parse_request("POST", "/cm/cty01/courses/search/$filters?courseGroupIds=0460001", b"").unwrap();
+
+        let response = search_courses(&request, &snapshots);
+        assert_eq!(response["data"].as_array().unwrap().len(), 1);
+        assert_eq!(response["data"][0]["name"], "Switching Systems");
+    }
+
+    #[test]
+    fn test_unknown_route() {
+        let request = parse_request("GET", "/unknown", b"").unwrap();
+        let (status, _) = route(&request, &sample_snapshots());
+        assert_eq!(status, "404 Not Found");
+    }
+}
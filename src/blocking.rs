@@ -0,0 +1,137 @@
+// synchronous version of the user facing api
+// every method runs the async implementation to completion on a runtime owned by the instance
+// so that scripts and command line tools don't have to manage a tokio runtime themselves
+use anyhow::Result;
+use crate::api::cache::CacheConfig;
+use crate::api::disk_cache::DiskCacheConfig;
+use crate::models::{CourseDetail, CourseInfo, Term};
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// Blocking counterpart of [`crate::CCNYCourseCatalog`].
+///
+/// Each instance owns a tokio runtime with a single worker thread that the requests are executed on,
+/// cloning an instance shares the runtime. Background work (i.e. refreshing a stale department listing,
+/// refer to [`CacheConfig::max_stale`]) keeps running on the worker thread between calls.
+///
+/// NOTE : the methods must not be called from within an async context (i.e. inside of `#[tokio::main]`),
+/// use [`crate::CCNYCourseCatalog`] directly there instead.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::blocking::CCNYCourseCatalog;
+///
+/// fn main() -> anyhow::Result<()> {
+///     let course_instance = CCNYCourseCatalog::new(String::from("computer science"), Some(String::from("data structures")))?;
+///     let list_of_courses = course_instance.get_courses_list()?;
+///     println!("{list_of_courses:#?}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CCNYCourseCatalog {
+    inner : crate::CCNYCourseCatalog,
+    runtime : Arc<Runtime>,
+}
+
+impl CCNYCourseCatalog {
+
+    /// # constructor function
+    /// - same parameters as [`crate::CCNYCourseCatalog::new`]
+    /// - fails if the underlying runtime cannot be created
+    pub fn new(department_name : String, course_name : Option<String>) -> Result<CCNYCourseCatalog> {
+        // a current thread runtime would only make progress on background refreshes during a later call
+        let runtime = Builder::new_multi_thread().worker_threads(1).enable_all().build()?;
+        Ok(CCNYCourseCatalog {
+            inner : crate::CCNYCourseCatalog::new(department_name, course_name),
+            runtime : Arc::new(runtime),
+        })
+    }
+
+    /// Retrieves list of courses based on name of department.
+    pub fn get_courses_list(&self) -> Result<Vec<CourseInfo>, anyhow::Error> {
+        self.runtime.block_on(self.inner.get_courses_list())
+    }
+
+    /// Retrieves information about the current course.
+    pub fn get_course_info(&self) -> Result<serde_json::Value, anyhow::Error> {
+        self.runtime.block_on(self.inner.get_course_info())
+    }
+
+    /// Same as [`CCNYCourseCatalog::get_course_info`], parsed into a [`CourseDetail`].
+    pub fn get_course_detail(&self) -> Result<CourseDetail, anyhow::Error> {
+        self.runtime.block_on(self.inner.get_course_detail())
+    }
+
+    /// Same as [`crate::CCNYCourseCatalog::get_course_info_by_group_id`].
+    pub fn get_course_info_by_group_id(&self, course_group_id : &str) -> Result<serde_json::Value, anyhow::Error> {
+        self.runtime.block_on(self.inner.get_course_info_by_group_id(course_group_id))
+    }
+
+    /// Same as [`crate::CCNYCourseCatalog::get_course_detail_by_group_id`].
+    pub fn get_course_detail_by_group_id(&self, course_group_id : &str) -> Result<CourseDetail, anyhow::Error> {
+        self.runtime.block_on(self.inner.get_course_detail_by_group_id(course_group_id))
+    }
+
+    /// Retrieves the academic terms known to the catalog (i.e. "2024 Fall Term").
+    pub fn get_terms(&self) -> Result<Vec<Term>, anyhow::Error> {
+        self.runtime.block_on(self.inner.get_terms())
+    }
+
+    /// Returns list of departments available within CUNY City College of New York.
+    pub fn get_department_list(&self) -> Vec<String> {
+        self.inner.get_department_list()
+    }
+
+    /// Setter methods allows modification of department and courses.
+    pub fn set_department_name(&mut self, new_department_name : &str) {
+        self.inner.set_department_name(new_department_name);
+    }
+
+    pub fn set_course_name(&mut self, new_course_name : &str) {
+        self.inner.set_course_name(new_course_name);
+    }
+
+    /// Same as [`crate::CCNYCourseCatalog::set_base_url`].
+    pub fn set_base_url(&mut self, base_url : &str) {
+        self.inner.set_base_url(base_url);
+    }
+
+    /// Replaces the in-memory response cache with an empty one using the provided configuration.
+    pub fn set_cache_config(&mut self, config : CacheConfig) {
+        self.inner.set_cache_config(config);
+    }
+
+    /// Enables (or with `None`, disables) the on-disk response cache.
+    pub fn set_disk_cache(&mut self, config : Option<DiskCacheConfig>) {
+        self.inner.set_disk_cache(config);
+    }
+
+    /// Removes every cached department listing and course detail, from memory and from disk.
+    pub fn clear_cache(&self) -> Result<()> {
+        self.inner.clear_cache()
+    }
+
+    /// Returns the async instance the requests are delegated to.
+    pub fn as_async(&self) -> &crate::CCNYCourseCatalog {
+        &self.inner
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_background_tasks_run_between_calls() {
+        let course_instance = CCNYCourseCatalog::new(String::from("computer science"), None).unwrap();
+
+        // spawned the same way as the refresh of a stale listing, without any later call to drive it
+        let (sender, receiver) = mpsc::channel();
+        course_instance.runtime.spawn(async move { sender.send(()).unwrap() });
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
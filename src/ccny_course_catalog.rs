@@ -1,17 +1,37 @@
 // This is the file that will handle the implementation logic
 // This will be the user facing file
 use anyhow::Result;
-use crate::api::department::{self, fetch_courses_by_department};
-use crate::models::CourseInfo;
-use crate::api::course_finder::retrieve_specific_course_info;
+use crate::api::cache::{CacheConfig, CacheLookup, CatalogCache};
+use crate::api::disk_cache::{DiskCache, DiskCacheConfig, ResponseKind};
+use crate::api::department::{self, fetch_courses_by_department_helper};
+use crate::api::course_finder::{retrieve_course_info_by_group_id, retrieve_historical_terms};
+use crate::api::sans_io;
+use crate::models::{CourseDetail, CourseInfo, Term};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CCNYCourseCatalog {
         pub department_name : String,       
-        pub course_name : String
+        pub course_name : String,
+
+        // responses retrieved so far, shared between clones of the instance
+        // the cache is safe to use from concurrent tasks, so clones can be handed to tokio::spawn
+        #[serde(skip)]
+        cache : CatalogCache,
+
+        // Coursedog API the requests are sent to, refer to set_base_url
+        #[serde(skip, default = "department::coursedog_base_url")]
+        base_url : String
     }
 
+// two instances are equal if they refer to the same department and course, regardless of what they have cached
+impl PartialEq for CCNYCourseCatalog {
+    fn eq(&self, other : &Self) -> bool {
+        self.department_name == other.department_name && self.course_name == other.course_name
+    }
+}
+
 impl CCNYCourseCatalog {
 
     /// # constructor function
@@ -29,8 +49,10 @@ impl CCNYCourseCatalog {
     /// ```
     pub fn new(department_name : String, course_name : Option<String>) ->   CCNYCourseCatalog {
         CCNYCourseCatalog {
-            department_name : department_name,
-            course_name : course_name.unwrap_or(String::from(""))
+            department_name,
+            course_name : course_name.unwrap_or_default(),
+            cache : CatalogCache::default(),
+            base_url : department::coursedog_base_url()
         }
     }
 
@@ -39,13 +61,13 @@ impl CCNYCourseCatalog {
     /// - Based on name of department
     /// 
     /// Example Usage:
-    /// /// # constructor function
-    /// - Example Usage
-    /// ```rust
+    /// ```rust,no_run
+    /// # use ccny_course_catalog::CCNYCourseCatalog;
+    /// # use anyhow::Result;
     /// #[tokio::main]
     /// async fn main() -> Result<()> {
     ///     let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), Some(String::from("data structures")));
-
+    ///
     ///     // return type of list_of_courses
     ///     // Result<Vec<CourseInfo>, anyhow::Error>
     ///     let mut list_of_courses = course_instance.get_courses_list().await;
@@ -157,149 +179,156 @@ impl CCNYCourseCatalog {
     /// //         ...additional courses list continued
     /// //     ]
     /// // )
- 
+    ///
     /// }
     /// ```
     pub async fn get_courses_list(&self) -> Result<Vec<CourseInfo>, anyhow::Error> {
-        fetch_courses_by_department(&self.department_name).await
+        let Some(department_code) = sans_io::resolve_department_code(&self.department_name) else {
+            eprintln!("A department by this name doesn't exist, please refer to the list of departments.");
+            return Err(anyhow::Error::msg("Program Failed"));
+        };
+
+        self.department_courses(&department_code).await
     }
 
     /// Retrieves information about the current course.
-    /// ```rust
+    /// ```rust,no_run
+    /// # use ccny_course_catalog::CCNYCourseCatalog;
+    /// # use anyhow::Result;
     /// #[tokio::main]
-/// async fn main() -> Result<()> {
-///     let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), Some(String::from("data structures")));
-
-///     // return tyep of list_of_courses
-///     // Result<Vec<CourseInfo>, anyhow::Error>
-///     let mut list_of_courses = course_instance.get_courses_list().await;
-///     let mut course_info = course_instance.get_course_info().await;
-///     println!("{course_info:#?}");
-///     Ok(())
-/// 
-/// // Resulting Output:
-/// //     Ok(
-/// //     Object {
-/// //         "data": Array [
-/// //             Object {
-/// //                 "_id": String("0455351-1901-01-01"),
-/// //                 "career": String("Undergraduate"),
-/// //                 "code": String("CSC 21200"),
-/// //                 "college": String("ENGR - Grove School of Engineering"),
-/// //                 "components": Array [
-/// //                     Object {
-/// //                         "attendanceContactUse": Bool(true),
-/// //                         "attendanceGenerate": Bool(false),
-/// //                         "attendanceLeftUse": Bool(true),
-/// //                         "attendancePresentUse": Bool(true),
-/// //                         "attendanceReasonUse": Bool(true),
-/// //                         "attendanceTardyUse": Bool(true),
-/// //                         "attendanceTemplateOverride": Bool(false),
-/// //                         "attendanceTimeUse": Bool(true),
-/// //                         "attendanceType": String("Class Meeting"),
-/// //                         "autoCreate": Bool(false),
-/// //                         "code": String("LEC"),
-/// //                         "contactHours": Number(4),
-/// //                         "customFields": Object {},
-/// //                         "defaultSectionSize": Number(35),
-/// //                         "deliveryMode": Array [],
-/// //                         "examSeatSpacing": Number(1),
-/// //                         "finalExamType": String("Yes"),
-/// //                         "id": String("LEC"),
-/// //                         "includeInDynamicDate": Bool(false),
-/// //                         "instructionMode": String("In Person"),
-/// //                         "lmsFileType": String("Blackboard CourseInfo 4"),
-/// //                         "name": String("Lecture"),
-/// //                         "oeeWorkloadHours": Number(0),
-/// //                         "optionalComponent": Bool(false),
-/// //                         "preferredRoomFeatures": Array [
-/// //                             String("Academic Scheduling"),
-/// //                         ],
-/// //                         "providerForAuthentication": String(""),
-/// //                         "workloadHours": Number(4),
-/// //                     },
-/// //                 ],
-/// //                 "consent": String("No Special Consent Required"),
-/// //                 "courseEquivalencies": Array [],
-/// //                 "courseGroupId": String("0455351"),
-/// //                 "courseNumber": String("21200"),
-/// //                 "courseTypicallyOffered": String("Fall, Spring"),
-/// //                 "credits": Object {
-/// //                     "academicProgressHours": Object {
-/// //                         "operator": String(""),
-/// //                         "value": Number(3),
-/// //                     },
-/// //                     "contactHours": Object {
-/// //                         "operator": String(""),
-/// //                         "value": Number(4),
-/// //                     },
-/// //                     "courseCount": Number(1),
-/// //                     "creditHours": Object {
-/// //                         "max": Number(3),
-/// //                         "min": Number(3),
-/// //                         "operator": String(""),
-/// //                     },
-/// //                     "financialAidHours": Object {
-/// //                         "operator": String(""),
-/// //                         "value": Number(3),
-/// //                     },
-/// //                     "numberOfCredits": Number(3),
-/// //                     "numberOfRepeats": Number(1),
-/// //                     "repeatable": Bool(false),
-/// //                 },
-/// //                 "customFields": Object {
-/// //                     "catalogAttributes": Array [],
-/// //                     "cuLibartsFlag": Bool(false),
-/// //                     "rawCourseId": String("045535"),
-/// //                 },
-/// //                 "departmentOwnership": Array [],
-/// //                 "departments": Array [
-/// //                     Object {
-/// //                         "_id": String("CSC-CTY"),
-/// //                         "campus": String("MAIN"),
-/// //                         "chair": Array [],
-/// //                         "displayName": String("Computer Science"),
-/// //                         "effectiveEndDate": String(""),
-/// //                         "effectiveStartDate": String(""),
-/// //                         "id": String("CSC-CTY"),
-/// //                         "institution": String("CTY01"),
-/// //                         "lastSyncErrorRecommendations": Array [],
-/// //                         "lastSyncErrors": Array [],
-/// //                         "lastSyncMergeReportId": String("gztrPMIGtByV2OgAAo2a"),
-/// //                         "lastSyncStatus": String("success"),
-/// //                         "lastSyncedAt": Number(1738733625029),
-/// //                         "name": String("Computer Science"),
-/// //                         "scheduleStatus": Object {
-/// //                             "2023": Object {},
-/// //                         },
-/// //                         "status": String("Active"),
-/// //                     },
-/// //                 ],
-/// //                 "description": String("Extension of the knowledge of algorithm design and programming gained in CSC 10300 with continued emphasis on the logic underlying the transition from specification to program. Particular attention is paid to issues arising in the implementation of larger programs: introduction of data structures and data abstraction; the basics of object-oriented programming. Introduction of recursion as a design tool. Introduction of complexity analysis"),
-/// //                 "effectiveEndDate": Null,
-/// //                 "effectiveStartDate": String("1901-01-01"),
-/// //                 "id": String("0455351-1901-01-01"),
-/// //                 "institution": String("CTY01"),
-/// //                 "institutionId": String("045535"),
-/// //                 "longName": String("Data Structures"),
-/// //                 "name": String("Data Structures"),
-/// //                 "orderByKeyForCode": String("CSC0000021200"),
-/// //                 "requirementGroup": String("019393"),
-/// //                 "status": String("Active"),
-/// //                 "subjectCode": String("CSC"),
-/// //                 "topics": Array [],
-/// //             },
-/// //         ],
-/// //         "limit": Number(50),
-/// //         "listLength": Number(1),
-/// //         "skip": Number(0),
-/// //     },
-/// // )
- 
-/// }
-/// ```
+    /// async fn main() -> Result<()> {
+    ///     let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), Some(String::from("data structures")));
+    ///
+    ///     // return tyep of list_of_courses
+    ///     // Result<Vec<CourseInfo>, anyhow::Error>
+    ///     let mut list_of_courses = course_instance.get_courses_list().await;
+    ///     let mut course_info = course_instance.get_course_info().await;
+    ///     println!("{course_info:#?}");
+    ///     Ok(())
+    /// 
+    /// // Resulting Output:
+    /// //     Ok(
+    /// //     Object {
+    /// //         "data": Array [
+    /// //             Object {
+    /// //                 "_id": String("0455351-1901-01-01"),
+    /// //                 "career": String("Undergraduate"),
+    /// //                 "code": String("CSC 21200"),
+    /// //                 "college": String("ENGR - Grove School of Engineering"),
+    /// //                 "components": Array [
+    /// //                     Object {
+    /// //                         "attendanceContactUse": Bool(true),
+    /// //                         "attendanceGenerate": Bool(false),
+    /// //                         "attendanceLeftUse": Bool(true),
+    /// //                         "attendancePresentUse": Bool(true),
+    /// //                         "attendanceReasonUse": Bool(true),
+    /// //                         "attendanceTardyUse": Bool(true),
+    /// //                         "attendanceTemplateOverride": Bool(false),
+    /// //                         "attendanceTimeUse": Bool(true),
+    /// //                         "attendanceType": String("Class Meeting"),
+    /// //                         "autoCreate": Bool(false),
+    /// //                         "code": String("LEC"),
+    /// //                         "contactHours": Number(4),
+    /// //                         "customFields": Object {},
+    /// //                         "defaultSectionSize": Number(35),
+    /// //                         "deliveryMode": Array [],
+    /// //                         "examSeatSpacing": Number(1),
+    /// //                         "finalExamType": String("Yes"),
+    /// //                         "id": String("LEC"),
+    /// //                         "includeInDynamicDate": Bool(false),
+    /// //                         "instructionMode": String("In Person"),
+    /// //                         "lmsFileType": String("Blackboard CourseInfo 4"),
+    /// //                         "name": String("Lecture"),
+    /// //                         "oeeWorkloadHours": Number(0),
+    /// //                         "optionalComponent": Bool(false),
+    /// //                         "preferredRoomFeatures": Array [
+    /// //                             String("Academic Scheduling"),
+    /// //                         ],
+    /// //                         "providerForAuthentication": String(""),
+    /// //                         "workloadHours": Number(4),
+    /// //                     },
+    /// //                 ],
+    /// //                 "consent": String("No Special Consent Required"),
+    /// //                 "courseEquivalencies": Array [],
+    /// //                 "courseGroupId": String("0455351"),
+    /// //                 "courseNumber": String("21200"),
+    /// //                 "courseTypicallyOffered": String("Fall, Spring"),
+    /// //                 "credits": Object {
+    /// //                     "academicProgressHours": Object {
+    /// //                         "operator": String(""),
+    /// //                         "value": Number(3),
+    /// //                     },
+    /// //                     "contactHours": Object {
+    /// //                         "operator": String(""),
+    /// //                         "value": Number(4),
+    /// //                     },
+    /// //                     "courseCount": Number(1),
+    /// //                     "creditHours": Object {
+    /// //                         "max": Number(3),
+    /// //                         "min": Number(3),
+    /// //                         "operator": String(""),
+    /// //                     },
+    /// //                     "financialAidHours": Object {
+    /// //                         "operator": String(""),
+    /// //                         "value": Number(3),
+    /// //                     },
+    /// //                     "numberOfCredits": Number(3),
+    /// //                     "numberOfRepeats": Number(1),
+    /// //                     "repeatable": Bool(false),
+    /// //                 },
+    /// //                 "customFields": Object {
+    /// //                     "catalogAttributes": Array [],
+    /// //                     "cuLibartsFlag": Bool(false),
+    /// //                     "rawCourseId": String("045535"),
+    /// //                 },
+    /// //                 "departmentOwnership": Array [],
+    /// //                 "departments": Array [
+    /// //                     Object {
+    /// //                         "_id": String("CSC-CTY"),
+    /// //                         "campus": String("MAIN"),
+    /// //                         "chair": Array [],
+    /// //                         "displayName": String("Computer Science"),
+    /// //                         "effectiveEndDate": String(""),
+    /// //                         "effectiveStartDate": String(""),
+    /// //                         "id": String("CSC-CTY"),
+    /// //                         "institution": String("CTY01"),
+    /// //                         "lastSyncErrorRecommendations": Array [],
+    /// //                         "lastSyncErrors": Array [],
+    /// //                         "lastSyncMergeReportId": String("gztrPMIGtByV2OgAAo2a"),
+    /// //                         "lastSyncStatus": String("success"),
+    /// //                         "lastSyncedAt": Number(1738733625029),
+    /// //                         "name": String("Computer Science"),
+    /// //                         "scheduleStatus": Object {
+    /// //                             "2023": Object {},
+    /// //                         },
+    /// //                         "status": String("Active"),
+    /// //                     },
+    /// //                 ],
+    /// //                 "description": String("Extension of the knowledge of algorithm design and programming gained in CSC 10300 with continued emphasis on the logic underlying the transition from specification to program. Particular attention is paid to issues arising in the implementation of larger programs: introduction of data structures and data abstraction; the basics of object-oriented programming. Introduction of recursion as a design tool. Introduction of complexity analysis"),
+    /// //                 "effectiveEndDate": Null,
+    /// //                 "effectiveStartDate": String("1901-01-01"),
+    /// //                 "id": String("0455351-1901-01-01"),
+    /// //                 "institution": String("CTY01"),
+    /// //                 "institutionId": String("045535"),
+    /// //                 "longName": String("Data Structures"),
+    /// //                 "name": String("Data Structures"),
+    /// //                 "orderByKeyForCode": String("CSC0000021200"),
+    /// //                 "requirementGroup": String("019393"),
+    /// //                 "status": String("Active"),
+    /// //                 "subjectCode": String("CSC"),
+    /// //                 "topics": Array [],
+    /// //             },
+    /// //         ],
+    /// //         "limit": Number(50),
+    /// //         "listLength": Number(1),
+    /// //         "skip": Number(0),
+    /// //     },
+    /// // )
+    ///
+    /// }
+    /// ```
     pub async fn get_course_info(&self) -> Result<serde_json::Value, anyhow::Error> {
-        if self.course_name == "".to_owned() {
+        if self.course_name.is_empty() {
             eprintln!("course name is empty, please specify a specific course name to retrieve information about a particular course.\n\n
                 
             HINT : use the method .set_course_name('name of course here')");
@@ -307,11 +336,54 @@ impl CCNYCourseCatalog {
         }
 
         // otherwise, if course_name does exist
-        // call on the function
-        // retrieve_specific_course_info
-        retrieve_specific_course_info(&self.course_name, &self.department_name).await
+        // determine the course group id of the closest matching course within the (possibly cached) department
+        let courses_by_department = self.get_courses_list().await?;
+        let course_group_id = sans_io::find_course_group_id(&courses_by_department, &self.course_name)
+            .ok_or(anyhow::Error::msg("Course Group ID Does not exist for this course"))?;
+
+        self.get_course_info_by_group_id(&course_group_id).await
+    }
+
+    /// Same as [`CCNYCourseCatalog::get_course_info`], for the course with the given course group id (i.e. `course.course_group_id`).
+    ///
+    /// Unlike the name based lookup, this always returns the intended course when several courses share a name
+    /// (i.e. "Independent Study"), and doesn't depend on the current department or course name.
+    pub async fn get_course_info_by_group_id(&self, course_group_id : &str) -> Result<serde_json::Value, anyhow::Error> {
+        let course_group_id = sans_io::normalize_course_group_id(course_group_id);
+
+        // concurrent lookups of the same course (i.e. from clones of the catalog) share a single load
+        let (cache, base_url) = (self.cache.clone(), self.base_url.clone());
+        let key = course_group_id.clone();
+        self.cache.course_details.get_or_load(&course_group_id, move || load_course_info(cache, base_url, key)).await
     }
     
+    /// Same as [`CCNYCourseCatalog::get_course_info`], parsed into a [`CourseDetail`].
+    pub async fn get_course_detail(&self) -> Result<CourseDetail, anyhow::Error> {
+        first_course_detail(&self.get_course_info().await?)
+    }
+
+    /// Same as [`CCNYCourseCatalog::get_course_info_by_group_id`], parsed into a [`CourseDetail`].
+    /// ```rust,no_run
+    /// # use ccny_course_catalog::CCNYCourseCatalog;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let course_instance = CCNYCourseCatalog::new(String::from("computer science"), None);
+    /// for course in course_instance.get_courses_list().await? {
+    ///     let course_detail = course_instance.get_course_detail_by_group_id(&course.course_group_id).await?;
+    ///     println!("{} : {}", course_detail.course_code, course_detail.description);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_course_detail_by_group_id(&self, course_group_id : &str) -> Result<CourseDetail, anyhow::Error> {
+        first_course_detail(&self.get_course_info_by_group_id(course_group_id).await?)
+    }
+
+    /// Retrieves the academic terms known to the catalog (i.e. "2024 Fall Term").
+    pub async fn get_terms(&self) -> Result<Vec<Term>, anyhow::Error> {
+        retrieve_historical_terms(&self.base_url).await
+    }
+
     /// Returns list of departments available within CUNY City College of New York.
     pub fn get_department_list(&self) -> Vec<String> {
         department::get_department_list()
@@ -320,10 +392,11 @@ impl CCNYCourseCatalog {
     /// Setter methods allows modification of department and courses.
     /// Setter logic examples
     /// ```
+    /// # use ccny_course_catalog::CCNYCourseCatalog;
+    /// # let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), None);
     /// course_instance.set_department_name("electrical engineering");
     /// course_instance.set_course_name("statistics");
     /// ```
-
     pub fn set_department_name(&mut self, new_department_name : &str) {
         self.department_name = String::from(new_department_name);
     }
@@ -331,4 +404,144 @@ impl CCNYCourseCatalog {
     pub fn set_course_name(&mut self, new_course_name : &str) {
         self.course_name = String::from(new_course_name);
     }
+
+    /// Sends the requests of this instance (and of clones made afterwards) to another Coursedog API,
+    /// i.e. the `coursedog-mock` stand-in server. Defaults to the `CCNY_COURSEDOG_BASE_URL` environment variable,
+    /// or to the public Coursedog API if it isn't set.
+    ///
+    /// Responses cached in memory before the call are still served, use [`CCNYCourseCatalog::clear_cache`] to drop them.
+    /// The disk cache keeps the responses of every API apart, so it never serves responses of another API.
+    /// ```
+    /// # use ccny_course_catalog::CCNYCourseCatalog;
+    /// # let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), None);
+    /// course_instance.set_base_url("http://127.0.0.1:8787");
+    /// assert_eq!(course_instance.base_url(), "http://127.0.0.1:8787");
+    /// ```
+    pub fn set_base_url(&mut self, base_url : &str) {
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+        if let Some(disk) = &mut self.cache.disk {
+            disk.set_base_url(&self.base_url);
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Replaces the in-memory response cache with an empty one using the provided configuration.
+    /// Clones made before the call keep sharing the previous cache.
+    /// ```
+    /// # use ccny_course_catalog::{CacheConfig, CCNYCourseCatalog};
+    /// # use std::time::Duration;
+    /// # let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), None);
+    /// course_instance.set_cache_config(CacheConfig { capacity : 16, ttl : Duration::from_secs(600), ..CacheConfig::default() });
+    /// ```
+    pub fn set_cache_config(&mut self, config : CacheConfig) {
+        let disk = self.cache.disk.take();
+        self.cache = CatalogCache::new(config);
+        self.cache.disk = disk;
+    }
+
+    /// Enables (or with `None`, disables) the on-disk response cache.
+    /// Responses missing from memory are read from the directory before being downloaded,
+    /// and downloaded responses are written to it, so they survive process restarts.
+    /// ```
+    /// # use ccny_course_catalog::{CCNYCourseCatalog, DiskCacheConfig};
+    /// # let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), None);
+    /// course_instance.set_disk_cache(Some(DiskCacheConfig::new(std::env::temp_dir().join("ccny-course-catalog"))));
+    /// ```
+    pub fn set_disk_cache(&mut self, config : Option<DiskCacheConfig>) {
+        self.cache.disk = config.map(|config| DiskCache::new(config, &self.base_url));
+    }
+
+    /// Removes every cached department listing and course detail, from memory and from disk.
+    pub fn clear_cache(&self) -> Result<()> {
+        self.cache.clear();
+        match &self.cache.disk {
+            Some(disk) => disk.clear(),
+            None => Ok(()),
+        }
+    }
+
+    // retrieves the courses of a department, consulting the cache first
+    // stale listings (refer to CacheConfig::max_stale) are returned right away and refreshed in the background
+    async fn department_courses(&self, department_code : &str) -> Result<Vec<CourseInfo>> {
+        // stale listings are downloaded again rather than read back from disk, where they are likely just as stale
+        let read_disk = match self.cache.departments.lookup(department_code) {
+            CacheLookup::Fresh(courses) => return Ok(courses),
+            CacheLookup::Stale(courses) => {
+                // the refresh needs a tokio runtime to run on, otherwise the listing is downloaded right away
+                if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                    runtime.spawn(refresh_department_courses(self.cache.clone(), self.base_url.clone(), department_code.to_owned()));
+                    return Ok(courses);
+                }
+                false
+            }
+            CacheLookup::Missing => true,
+        };
+
+        // concurrent lookups of the same department (i.e. from clones of the catalog) share a single load
+        let (cache, base_url) = (self.cache.clone(), self.base_url.clone());
+        let key = department_code.to_owned();
+        self.cache.departments.load(department_code, move || load_department_courses(cache, base_url, key, read_disk)).await
+    }
+}
+
+// reads the courses of a department from disk (if enabled), otherwise downloads them
+// returned along with their age, so that a listing read from disk doesn't restart it's ttl once cached in memory
+async fn load_department_courses(cache : CatalogCache, base_url : String, department_code : String, read_disk : bool) -> Result<(Vec<CourseInfo>, Duration)> {
+    if let Some(disk) = cache.disk.as_ref().filter(|_| read_disk) {
+        if let Some(stored) = disk.read::<Vec<CourseInfo>>(ResponseKind::Departments, &department_code).await {
+            return Ok(stored);
+        }
+    }
+
+    let courses = download_department_courses(&cache, &base_url, &department_code).await?;
+    Ok((courses, Duration::ZERO))
+}
+
+// downloads the courses of a department and writes them to disk, storing them in memory is up to the caller
+async fn download_department_courses(cache : &CatalogCache, base_url : &str, department_code : &str) -> Result<Vec<CourseInfo>> {
+    let response = fetch_courses_by_department_helper(base_url, department_code).await?;
+    let courses = sans_io::department_courses_from_value(&response)?;
+    write_to_disk(cache, ResponseKind::Departments, department_code, &courses).await;
+    Ok(courses)
+}
+
+// same as load_department_courses, for the details of a course
+async fn load_course_info(cache : CatalogCache, base_url : String, course_group_id : String) -> Result<(serde_json::Value, Duration)> {
+    if let Some(disk) = &cache.disk {
+        if let Some(stored) = disk.read::<serde_json::Value>(ResponseKind::CourseDetails, &course_group_id).await {
+            return Ok(stored);
+        }
+    }
+
+    let course_info = retrieve_course_info_by_group_id(&base_url, &course_group_id).await?;
+    write_to_disk(&cache, ResponseKind::CourseDetails, &course_group_id, &course_info).await;
+    Ok((course_info, Duration::ZERO))
+}
+
+fn first_course_detail(course_info : &serde_json::Value) -> Result<CourseDetail> {
+    sans_io::course_details_from_value(course_info)?
+        .into_iter()
+        .next()
+        .ok_or(anyhow::Error::msg("the catalog didn't return any details for this course"))
+}
+
+// failing to write to the disk cache doesn't fail the request, the response is simply downloaded again next time
+async fn write_to_disk<V : Serialize>(cache : &CatalogCache, kind : ResponseKind, key : &str, value : &V) {
+    if let Some(disk) = &cache.disk {
+        if let Err(err) = disk.write(kind, key, value).await {
+            eprintln!("failed to write {key} to the disk cache : {err:#}");
+        }
+    }
+}
+
+// background refresh of a stale department listing
+// on failure the stale listing is kept, until it's too stale to be served
+async fn refresh_department_courses(cache : CatalogCache, base_url : String, department_code : String) {
+    match download_department_courses(&cache, &base_url, &department_code).await {
+        Ok(courses) => cache.departments.insert(&department_code, courses),
+        Err(err) => eprintln!("failed to refresh the courses of {department_code} : {err:#}"),
+    }
 }
\ No newline at end of file
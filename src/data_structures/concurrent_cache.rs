@@ -0,0 +1,365 @@
+// thread safe cache built on top of the custom LRU Cache
+// why is this needed?
+//
+// CustomLruCache requires &mut self even for get() (since a lookup updates the recency of the entry)
+// so it can't be shared between tokio tasks without a lock around it.
+// a single lock would make every concurrent lookup wait on every other lookup,
+// so the keys are split between several independently locked caches (shards) based on their hash.
+//
+// NOTE : recency is tracked per shard, so the evicted entry is the least recently used one of it's shard
+// rather than of the whole cache.
+//
+// missing keys can be loaded through get_or_insert_with, concurrent calls for the same missing key
+// share a single load (i.e. a single download) rather than each running their loader
+
+use crate::data_structures::custom_hashmap::CustomHashMap;
+use crate::data_structures::custom_lru_cache::{CacheStats, CustomLruCache};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::future::Future;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// a load awaited by every task looking up the same missing key
+// anyhow::Error isn't Clone, so the error is shared between the waiting tasks behind an Arc
+type PendingLoad<V> = Shared<BoxFuture<'static, Result<V, Arc<anyhow::Error>>>>;
+
+// cloning the cache is cheap, clones share the same shards (and the same pending loads)
+pub struct ConcurrentLruCache<K, V, S = RandomState> {
+    shards : Arc<[Mutex<CustomLruCache<K, V>>]>,
+
+    // loads that are still in flight, removed once their value is within the cache
+    loading : Arc<Mutex<CustomHashMap<K, PendingLoad<V>>>>,
+
+    // decides which shard a key belongs to
+    hash_builder : S,
+}
+
+impl<K, V, S : Clone> Clone for ConcurrentLruCache<K, V, S> {
+    fn clone(&self) -> Self {
+        ConcurrentLruCache {
+            shards : Arc::clone(&self.shards),
+            loading : Arc::clone(&self.loading),
+            hash_builder : self.hash_builder.clone(),
+        }
+    }
+}
+
+// the pending loads are futures, which can't be printed
+impl<K : Debug, V : Debug, S> Debug for ConcurrentLruCache<K, V, S> {
+    fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConcurrentLruCache")
+            .field("shards", &self.shards)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K : Hash + Eq + Clone, V> ConcurrentLruCache<K, V> {
+
+    // splits the capacity evenly between the shards (rounding up)
+    pub fn new(capacity : usize, shard_count : usize) -> Self {
+        let shard_capacity = capacity.div_ceil(shard_count.max(1));
+        ConcurrentLruCache::with_shards(shard_count, || CustomLruCache::new(shard_capacity))
+    }
+
+    // creates each shard using the provided function, allowing the shards to be configured
+    // (i.e. with a time to live, clock or weigher)
+    pub fn with_shards<F>(shard_count : usize, make_shard : F) -> Self
+    where
+        F : FnMut() -> CustomLruCache<K, V>,
+    {
+        ConcurrentLruCache::with_shards_and_hasher(shard_count, make_shard, RandomState::new())
+    }
+}
+
+impl<K : Hash + Eq + Clone, V, S : BuildHasher> ConcurrentLruCache<K, V, S> {
+
+    // same as with_shards, assigning keys to shards with the provided hasher
+    pub fn with_shards_and_hasher<F>(shard_count : usize, mut make_shard : F, hash_builder : S) -> Self
+    where
+        F : FnMut() -> CustomLruCache<K, V>,
+    {
+        let shards : Vec<Mutex<CustomLruCache<K, V>>> = (0..shard_count.max(1))
+            .map(|_| Mutex::new(make_shard()))
+            .collect();
+
+        ConcurrentLruCache {
+            shards : shards.into(),
+            loading : Arc::new(Mutex::new(CustomHashMap::new())),
+            hash_builder,
+        }
+    }
+
+    // returns a copy of the value, marking it as recently used
+    pub fn get(&self, key : &K) -> Option<V>
+    where
+        V : Clone,
+    {
+        self.shard(key).get(key).cloned()
+    }
+
+    // check if a key is present and hasn't expired, without marking it as recently used
+    pub fn contains_key(&self, key : &K) -> bool {
+        self.shard(key).contains_key(key)
+    }
+
+    // same as CustomLruCache::insert, the evictions only concern the shard of the key
+    pub fn insert(&self, key : K, value : V) -> Option<(K, V)> {
+        self.shard(&key).insert(key, value)
+    }
+
+    pub fn remove(&self, key : &K) -> Option<V> {
+        self.shard(key).remove(key)
+    }
+
+    // removes every expired entry of every shard, returning how many were removed
+    pub fn purge_expired(&self) -> usize {
+        self.shards.iter().map(|shard| lock(shard).purge_expired()).sum()
+    }
+
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            lock(shard).clear();
+        }
+    }
+
+    // number of entries across the shards
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| lock(shard).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| lock(shard).is_empty())
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    // counters of every shard added together
+    pub fn stats(&self) -> CacheStats {
+        self.shards.iter().fold(CacheStats::default(), |total, shard| {
+            let stats = lock(shard).stats();
+            CacheStats {
+                hits : total.hits + stats.hits,
+                misses : total.misses + stats.misses,
+                inserts : total.inserts + stats.inserts,
+                evictions : total.evictions + stats.evictions,
+                expirations : total.expirations + stats.expirations,
+            }
+        })
+    }
+
+    // the lock is only ever held for the duration of a single cache operation, never across an await point
+    fn shard(&self, key : &K) -> MutexGuard<'_, CustomLruCache<K, V>> {
+        let idx = (self.hash_builder.hash_one(key) % self.shards.len() as u64) as usize;
+        lock(&self.shards[idx])
+    }
+}
+
+impl<K, V, S> ConcurrentLruCache<K, V, S>
+where
+    K : Hash + Eq + Clone + Send + Sync + 'static,
+    V : Clone + Send + Sync + 'static,
+    S : BuildHasher + Clone + Send + Sync + 'static,
+{
+    // returns the cached value, otherwise awaits the loader and caches it's result
+    //
+    // the shard isn't locked while the loader runs, so other keys (and other tasks) aren't blocked by it,
+    // concurrent calls for the same missing key wait on the first call's load instead of running their own loader
+    pub async fn get_or_insert_with<F, Fut>(&self, key : K, loader : F) -> V
+    where
+        F : FnOnce() -> Fut,
+        Fut : Future<Output = V> + Send + 'static,
+    {
+        let value = self.load(key, move || loader().map(Ok), true).await;
+        value.expect("loaders that can't fail don't return errors")
+    }
+
+    // same as get_or_insert_with, for loaders that may fail
+    // the error is returned to every caller waiting on the load without being cached, so the next call loads the key again
+    pub async fn try_get_or_insert_with<F, Fut>(&self, key : K, loader : F) -> anyhow::Result<V>
+    where
+        F : FnOnce() -> Fut,
+        Fut : Future<Output = anyhow::Result<V>> + Send + 'static,
+    {
+        self.load(key, loader, true).await
+    }
+
+    // awaits the loader and caches it's result, even if the key is already cached (i.e. to replace a stale value)
+    // concurrent calls for the same key share a single load, like get_or_insert_with
+    pub async fn load_with<F, Fut>(&self, key : K, loader : F) -> anyhow::Result<V>
+    where
+        F : FnOnce() -> Fut,
+        Fut : Future<Output = anyhow::Result<V>> + Send + 'static,
+    {
+        self.load(key, loader, false).await
+    }
+
+    // if every task waiting on a load is cancelled, the next caller resumes the same load
+    async fn load<F, Fut>(&self, key : K, loader : F, use_cached : bool) -> anyhow::Result<V>
+    where
+        F : FnOnce() -> Fut,
+        Fut : Future<Output = anyhow::Result<V>> + Send + 'static,
+    {
+        let pending_load = {
+            let mut loading = self.loading.lock().expect("pending loads lock poisoned");
+
+            // looked up while the pending loads are locked, so that a load completing in between isn't missed
+            // (loads insert their value before removing themselves from the pending loads)
+            if use_cached {
+                if let Some(value) = self.get(&key) {
+                    return Ok(value);
+                }
+            }
+
+            match loading.get(&key) {
+                Some(pending_load) => pending_load.clone(),
+                None => {
+                    let pending_load = self.start_load(key.clone(), loader());
+                    loading.insert(key, pending_load.clone());
+                    pending_load
+                }
+            }
+        };
+
+        pending_load.await.map_err(|err| anyhow::Error::msg(format!("{err:#}")))
+    }
+
+    // wraps the load so that it caches it's value and removes itself from the pending loads once it completes
+    // this happens exactly once, before any of the waiting tasks receive the result
+    fn start_load<Fut>(&self, key : K, load : Fut) -> PendingLoad<V>
+    where
+        Fut : Future<Output = anyhow::Result<V>> + Send + 'static,
+    {
+        let cache = self.clone();
+        async move {
+            let result = load.await.map_err(Arc::new);
+            if let Ok(value) = &result {
+                cache.insert(key.clone(), value.clone());
+            }
+            cache.loading.lock().expect("pending loads lock poisoned").remove(&key);
+            result
+        }
+        .boxed()
+        .shared()
+    }
+}
+
+fn lock<K, V>(shard : &Mutex<CustomLruCache<K, V>>) -> MutexGuard<'_, CustomLruCache<K, V>> {
+    shard.lock().expect("cache shard lock poisoned")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_basic_operations() {
+        let cache = ConcurrentLruCache::new(256, 4);
+        assert_eq!(cache.shard_count(), 4);
+        for key in 0..32 {
+            cache.insert(key, key * 2);
+        }
+
+        assert_eq!(cache.len(), 32);
+        assert_eq!(cache.get(&10), Some(20));
+        assert_eq!(cache.remove(&10), Some(20));
+        assert!(!cache.contains_key(&10));
+
+        // clones share the same entries
+        let shared = cache.clone();
+        shared.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shared_between_tasks() {
+        let cache : ConcurrentLruCache<u32, String> = ConcurrentLruCache::new(1024, 8);
+        let tasks : Vec<_> = (0..16u32).map(|task| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                for key in 0..16u32 {
+                    cache.insert(task * 16 + key, format!("{task}-{key}"));
+                }
+            })
+        }).collect();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(cache.len(), 256);
+        assert_eq!(cache.get(&17), Some(String::from("1-1")));
+        assert_eq!(cache.stats().inserts, 256);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_insert_with() {
+        let cache : ConcurrentLruCache<&str, usize> = ConcurrentLruCache::new(8, 2);
+        let loads = Arc::new(AtomicUsize::new(0));
+        let load = || {
+            let loads = Arc::clone(&loads);
+            async move {
+                loads.fetch_add(1, Ordering::SeqCst);
+                42
+            }
+        };
+
+        assert_eq!(cache.get_or_insert_with("CSC-CTY", load).await, 42);
+        assert_eq!(cache.get_or_insert_with("CSC-CTY", load).await, 42);
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // failed loads aren't cached
+        let failed = cache.try_get_or_insert_with("EE-CTY", || async { Err(anyhow::Error::msg("offline")) }).await;
+        assert_eq!(failed.unwrap_err().to_string(), "offline");
+        assert!(!cache.contains_key(&"EE-CTY"));
+
+        // load_with replaces the cached value
+        assert_eq!(cache.load_with("CSC-CTY", || async { Ok(43) }).await.unwrap(), 43);
+        assert_eq!(cache.get(&"CSC-CTY"), Some(43));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_misses_share_one_load() {
+        let cache : ConcurrentLruCache<String, String> = ConcurrentLruCache::new(8, 2);
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        let tasks : Vec<_> = (0..20).map(|_| {
+            let cache = cache.clone();
+            let loads = Arc::clone(&loads);
+            tokio::spawn(async move {
+                cache.get_or_insert_with(String::from("CSC-CTY"), || async move {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    String::from("computer science")
+                }).await
+            })
+        }).collect();
+
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), "computer science");
+        }
+
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(cache.loading.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_shared_between_waiting_calls() {
+        let cache : ConcurrentLruCache<&str, u32> = ConcurrentLruCache::new(8, 1);
+        let failing = cache.try_get_or_insert_with("0455351", || async {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Err(anyhow::Error::msg("connection reset"))
+        });
+        let waiting = cache.try_get_or_insert_with("0455351", || async { Ok(1) });
+        let (failing, waiting) = tokio::join!(failing, waiting);
+
+        assert_eq!(failing.unwrap_err().to_string(), "connection reset");
+        assert_eq!(waiting.unwrap_err().to_string(), "connection reset");
+        assert_eq!(cache.try_get_or_insert_with("0455351", || async { Ok(2) }).await.unwrap(), 2);
+    }
+}
@@ -1,21 +1,36 @@
-use std::collections::hash_map::DefaultHasher;
+use std::collections::hash_map::RandomState;
 
 // Hash : a hashable type trait
 // Hasher : A trait for hashing an arbitary stream of bytes
-use std::hash::{Hash,Hasher};
-use std::cmp::PartialEq;        // PartialEq trait
-use std::marker::Copy;          // Copy trait that is attached to generic V
-// default max size of the underlying array
-const DEFAULT_MAX_SIZE : u64 = 256;
+// BuildHasher : creates the hasher used for every key, allowing the hashing algorithm to be swapped out
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::borrow::Borrow;        // allows looking up String keys with a &str, etc.
+
+// number of buckets allocated by the first insertion
+const INITIAL_BUCKET_COUNT : usize = 16;
+
+// the buckets are doubled once the number of elements exceeds 3/4 of the number of buckets
+// keeping the linked lists short, so that lookups remain close to constant time as the map grows
+const MAX_LOAD_FACTOR_NUMERATOR : usize = 3;
+const MAX_LOAD_FACTOR_DENOMINATOR : usize = 4;
+
+// a bucket is the head of a (possibly empty) linked list of key value pairs
+type Bucket<T,V> = Option<Box<KeyValue<T,V>>>;
 
 // maintains a current size, and it's underlying key value pairs.
+//
+// S decides how keys are hashed
+// - RandomState (default) : randomly seeded SipHash, resistant to collision attacks, for maps keyed by user input
+// - FnvBuildHasher : fast non-cryptographic hashing, for internal indexes keyed by short strings (i.e. course codes)
 #[derive(Debug, Clone)]
-pub struct CustomHashMap<T,V> {
+pub struct CustomHashMap<T, V, S = RandomState> {
     curr_size : usize,
 
-    // [Type of data for the elements; size of the overall array]
-    // originally, the elements within the array will all be set as None (refer to the definition of the constructor)
-    arr : [Option<KeyValue<T,V>>; DEFAULT_MAX_SIZE as usize],       
+    // the buckets are stored on the heap and grow along with the number of elements
+    // originally, no buckets are allocated (refer to the definition of the constructor)
+    buckets : Vec<Bucket<T,V>>,
+
+    hash_builder : S,
 }
 
 // Box is a simple form of heap allocation in rust.
@@ -25,9 +40,8 @@ pub struct KeyValue<T,V> {
     key : T,
     value : V,
 
-    // can use this to check if an element in the array has been initialised or not
     // refers to an optional next keyValue in a linked list, in the case of collisions
-    next : Option<Box<KeyValue<T,V>>>       
+    next : Bucket<T,V>
 }
 
 impl<T,V> KeyValue<T,V> {
@@ -50,282 +64,542 @@ impl<T,V> KeyValue<T,V> {
 // all methods should be wrapped around Option<> to handle None Cases in the event that the value doesn't exist.
 // underneath an hashmap, note the following : the keys are the indexes of the array, the values are LinkedLists representation (and thus can handle insertion of multiple values)
 //
-// T: Clone + Hash + PartialEq are all traits that are being attached to the generic T
-// V : Copy
+// T: Hash + Eq are the traits that are being attached to the generic T
+// V doesn't need any traits, values are returned by reference instead of being copied
 // Meaning, T and V will be the placeholder types
 // and those types will inherit the traits listed automatically
-impl<T : Clone + Hash + PartialEq, V : Copy> CustomHashMap<T,V> {
-    const INIT : Option<KeyValue<T,V>> = None;      // we use const to avoid running into borrow/ownership related errors that may arise
+impl<T : Hash + Eq, V> CustomHashMap<T,V> {
 
+    // no buckets are allocated until the first insertion
     pub fn new() -> CustomHashMap<T,V> {
+        CustomHashMap::with_hasher(RandomState::new())
+    }
+
+    // allocates enough buckets to hold `capacity` elements without resizing
+    pub fn with_capacity(capacity : usize) -> CustomHashMap<T,V> {
+        CustomHashMap::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T : Hash + Eq, V, S : BuildHasher> CustomHashMap<T,V,S> {
+
+    // same as new, hashing the keys with the provided hasher
+    pub fn with_hasher(hash_builder : S) -> CustomHashMap<T,V,S> {
+        CustomHashMap {
+            curr_size : 0,
+            buckets : Vec::new(),
+            hash_builder,
+        }
+    }
+
+    // same as with_capacity, hashing the keys with the provided hasher
+    pub fn with_capacity_and_hasher(capacity : usize, hash_builder : S) -> CustomHashMap<T,V,S> {
         CustomHashMap {
             curr_size : 0,
-            arr : [Self::INIT; DEFAULT_MAX_SIZE as usize],
+            buckets : empty_buckets(bucket_count_for(capacity)),
+            hash_builder,
         }
     }
 
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    // number of elements the map can hold before the buckets are resized
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * MAX_LOAD_FACTOR_NUMERATOR / MAX_LOAD_FACTOR_DENOMINATOR
+    }
+
     // inserts key and value pair
+    // returns the old value if the key was already present
     pub fn insert(&mut self, key : T, val : V) -> Option<V> {
-        // commented out for future reference
-        // todo!()         // macro to state it has not yet been implemented
-
-        let hash_val : u64 = hash_key(key.clone());
-        let position = hash_val % DEFAULT_MAX_SIZE;
-
-        match &self.arr[position as usize] {
-
-            // update_or_link_new_val is a private method wtihin the implementation
-            // what do we do if a value is already present at our index?
-            // consider the 2 following scenarios:
-            //
-            // traverse the linked list until we find a keyvalue with a matching key
-            // or we insert our new KeyValue pair onto the end of the Linked List
-            // we continue traversing within the current index until we reach the end of the Linked List
-            Some(_) => self.update_or_link_new_val(key,val, position as usize),
-            None => {
-                // insert_new_value is another private method within the implementation
-                // append a new node to the Linked List to the end of the LL once we have arrived at the end
-                // holds three information, the key, the value and the position within which the node is located
-                self.insert_new_value(key,val, position as usize);
-                None
-            }
+
+        // what do we do if a value is already present at our index?
+        // traverse the linked list until we find a keyvalue with a matching key and update it
+        if let Some(value) = self.get_mut(&key) {
+            return Some(std::mem::replace(value, val));
         }
+
+        // otherwise the key is new
+        self.insert_new(key, val);
+        None
     }
 
+    // retrieves a reference to the value given a key
+    // the key can be any borrowed form of T (i.e. &str for String keys), like std::collections::HashMap
+    //
+    // same approach is applied, hashing our key to find an index of the array to use (this is the core)
+    pub fn get<Q>(&self, key : &Q) -> Option<&V>
+    where
+        T : Borrow<Q>,
+        Q : Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|node| &node.value)
+    }
 
-    // function definition for inserting a new value to the linked list
-    fn insert_new_value(&mut self, key : T, val : V, position : usize) {
+    // retrieves a mutable reference to the value given a key
+    pub fn get_mut<Q>(&mut self, key : &Q) -> Option<&mut V>
+    where
+        T : Borrow<Q>,
+        Q : Hash + Eq + ?Sized,
+    {
+        let position = self.position(key)?;
+
+        // we are using as_deref_mut() since the value is handed out mutably
+        let mut current = self.buckets[position].as_deref_mut();
+        while let Some(node) = current {
+            if node.key.borrow() == key {
+                return Some(&mut node.value);
+            }
+            current = node.next.as_deref_mut();
+        }
 
-        // calls upon the KeyValue's constructor to create a new key and value pair
-        // which is then set to the current position of the array (essentially an array of linked list)
-        let new_entry = KeyValue::new(key,val);
+        None
+    }
 
-        self.arr[position] = Some(new_entry);
-        self.curr_size += 1;
+    // retrieves the stored key along with the value
+    pub fn get_key_value<Q>(&self, key : &Q) -> Option<(&T, &V)>
+    where
+        T : Borrow<Q>,
+        Q : Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|node| (&node.key, &node.value))
     }
 
-    fn update_or_link_new_val(&mut self, key : T, val : V, position : usize) -> Option<V> {
-        // Traverse linked list until either find value and update it (meaning the node already exists)
-        // or insert a new value at the end of the linked list
-        //
-        // can safely unwrap because we have verified that this position exists
-        // grab the key value at a particular position in order to check if they matches
-        //
-        // since the nodes stores the key and values as pairs
-        // meaning key_val.key and key_val.value are both feasible as they are part of the KeyVal struct
-        let key_val = self.arr[position].as_mut().unwrap();
+    pub fn contains_key<Q>(&self, key : &Q) -> bool
+    where
+        T : Borrow<Q>,
+        Q : Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
 
-        // meaning the key already exists within the head node, and we need to update the existing value corresponding to the key
-        if key_val.key == key {
+    // removes the key-value pair from the map for a given key, return the value if key existed
+    // None otherwise
+    pub fn remove<Q>(&mut self, key : &Q) -> Option<V>
+    where
+        T : Borrow<Q>,
+        Q : Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
 
-            // store the old value within this variable
-            let old_val = key_val.value;
+    // same as remove, but the stored key is returned as well
+    //
+    // the logic of removing from a hashmap underneath is the same as removing from a linked list
+    // we walk the links until the one pointing to the matching node is found
+    // and then have that link point to the node after the removed one instead
+    pub fn remove_entry<Q>(&mut self, key : &Q) -> Option<(T, V)>
+    where
+        T : Borrow<Q>,
+        Q : Hash + Eq + ?Sized,
+    {
+        let position = self.position(key)?;
+        let mut link = &mut self.buckets[position];
+        while link.as_ref().is_some_and(|node| node.key.borrow() != key) {
+            link = &mut link.as_mut().unwrap().next;
+        }
 
-            // update the current key's corresponding value
-            // with the new value with the value that is passed onto the parameter
-            key_val.value = val;
+        let mut removed = link.take()?;
+        *link = removed.next.take();
+        self.curr_size -= 1;
+        Some((removed.key, removed.value))
+    }
 
-            return Some(old_val);     // return the old value
+    // gets the entry of a key for in-place manipulation
+    // (i.e. *map.entry(key).or_insert(0) += 1)
+    pub fn entry(&mut self, key : T) -> Entry<'_, T, V, S> {
+        if self.contains_key(&key) {
+            Entry::Occupied(OccupiedEntry { map : self, key })
+        } else {
+            Entry::Vacant(VacantEntry { map : self, key })
         }
+    }
 
-        // otherwise, if the key doesn't exist in the first node
-        // it may exists within the middle of the Linked list instead
-        // we need to create a new linked list and attach it to the end
-        //
-        // is_some() : returns True if the option is a Some value.
-        // iterates through the linked list using is_some() to check if we've reached the end of the linked list
-        //
-        // for every existing node, the following checks needs to be performed 
-        // 1. if that node's key matches ours
-        // 2. if it does, we update the value and return the old value held there
-        // 3. if not, move to the next node
-        let mut current = key_val;
-        while current.next.is_some() {          // linked list traversal logic
-            let node = current.next.as_mut().unwrap();
-
-            if node.key == key {
-                // update the value 
-                let old_val = node.value;
-                node.value = val;
-                return Some(old_val);
-            }
+    // clears the hashmap
+    // similar to the drain method within the std::collections::HashMap
+    //
+    // the buckets are kept allocated, so that refilling the map doesn't resize it again
+    pub fn clear(&mut self) {
+        self.curr_size = 0;
+        self.buckets.iter_mut().for_each(|bucket| *bucket = None);
+    }
 
-            current = node;    
-        }
+    pub fn len(&self) -> usize {
+        self.curr_size
+    }
 
-        // append the new value to the end of the linked list
-        let new_key_val = KeyValue::new(key,val);
-        current.next = Some(Box::new(new_key_val));
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-        self.curr_size += 1;        // since linked list has been increased by 1, we also need to increment the current size
+    // iterates over the key value pairs, in no particular order
+    pub fn iter(&self) -> Iter<'_, T, V> {
+        Iter {
+            buckets : self.buckets.iter(),
+            current : None,
+            remaining : self.curr_size,
+        }
+    }
 
-        None
+    // iterates over the key value pairs, with mutable references to the values
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, V> {
+        IterMut {
+            buckets : self.buckets.iter_mut(),
+            current : None,
+            remaining : self.curr_size,
+        }
+    }
 
+    pub fn keys(&self) -> impl Iterator<Item = &T> {
+        self.iter().map(|(key, _)| key)
     }
 
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
 
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.iter_mut().map(|(_, value)| value)
+    }
 
-    // retrieves a value given a key
-    // grab values from the hashmap
-    //
-    // same approach is applied, hashing our key to find an index of the array to use (this is the core)
-    pub fn get(&self, key : T) -> Option<V> {
-        let hash_val : u64 = hash_key(key.clone());
-        let position = hash_val % DEFAULT_MAX_SIZE;
-        
-        // after obtaining the position, the goal is to check the value at the particular index
-        // check_list_for_key() is a private method within the hashmap (implemented below)
-        // the method takes in the key that we are looking for within a particular position to check if it exists
-        // _ serves as a placeholder for return value that isn't None
-        match &self.arr[position as usize] {
-            Some(_) => self.check_list_for_key(key, position as usize),
-            None => None,       // if there's no value, simply return None
-        }  
-    }
-
-    fn check_list_for_key(&self, key : T, position : usize) -> Option<V> {
-        let mut current = self.arr[position].as_ref().unwrap();
-        if current.key == key {
-            return Some(current.value);
-        }
+    // walks the linked list of the key's bucket looking for the node holding the key
+    fn find<Q>(&self, key : &Q) -> Option<&KeyValue<T,V>>
+    where
+        T : Borrow<Q>,
+        Q : Hash + Eq + ?Sized,
+    {
+        let position = self.position(key)?;
 
-        // we are using as_ref() instead of using as_mut()
+        // we are using as_deref() instead of using as_deref_mut()
         // since we don't need to mutate any of the values
-        while let Some(node) = current.next.as_ref() {
-            if node.key == key {
-                return Some(node.value);
+        let mut current = self.buckets[position].as_deref();
+        while let Some(node) = current {
+            if node.key.borrow() == key {
+                return Some(node);
             }
-
-            current = node;
+            current = node.next.as_deref();
         }
 
         None
     }
 
-    // removes the key-value pair from the map for a given key, return the value if key existed
-    // None otherwise
-    //
-    // again, we hash the key and retrieve the remainder of DEFAULT_MAX_SIZE to retrieve the position
-    // if that position is empty, we return None, otherwise we traverse through the linked list looking for the particular value
-    //
-    // the logic of removing from a hashmap underneath is the same as removing from a linked list
-    // in order to remove a value from a Linked List the following steps must be followed:
-    // 1. check if the KeyValue being removed has a next value
-    // 2. if it does, we set the next value of the "previous" KeyValue to point to this KeyValue
-    // 3. If the KeyValue is being removed is the first value, we set the value in the array to be the next value, if it exists
-    pub fn remove(&mut self, key : T) -> Option<V> {
-        let hash_val : u64 = hash_key(key.clone());
-        let position : u64 = hash_val % DEFAULT_MAX_SIZE;
-
-        // fairly straightforward logic, we once again check within the position of the array
-        // check if the node exists and call on the helper function
-        // the helper in this case being check_item_in_list_and_remove
-        match &self.arr[position as usize] {
-            Some(_) => self.check_item_in_list_and_remove(key, position as usize),
-            None => None,           // same as not doing anything since the key doesn't exist
+    // inserts a key that is known not to be present, returning a reference to it's value
+    fn insert_new(&mut self, key : T, val : V) -> &mut V {
+        // grow the buckets beforehand if the load factor would be exceeded
+        if self.curr_size + 1 > self.capacity() {
+            self.resize(bucket_count_for(self.curr_size + 1).max(self.buckets.len() * 2));
         }
+
+        // prepend the new node to the linked list, avoiding a second traversal
+        let position = self.position(&key).unwrap();
+        let mut new_entry = Box::new(KeyValue::new(key, val));
+        new_entry.next = self.buckets[position].take();
+        self.curr_size += 1;
+        &mut self.buckets[position].insert(new_entry).value
     }
 
-    // private helper function for the remove method 
-    fn check_item_in_list_and_remove(&mut self, key : T, position : usize) -> Option<V> {
-        let mut current = self.arr[position].as_mut().unwrap();
-
-        // check if the first node's key matches the value
-        // if it does, we save the value it held so that we can return it later
-        //
-        // we then check if next exists, if it doesn't, we simply update that position to be None
-        // if it does exist, we set that position to be the address of that node (hence the use of *)
-        if current.key == key {
-            let return_val = current.value;
-
-            // check if there is a next value
-            // if there is next, update array to point to this particular value
-            // this conditional will continue to execute while current.next.to_owned() returns Some(node)
-            if let Some(node) = current.next.to_owned() {
-                self.arr[position] = Some(*node);
-            } else {
-                self.arr[position] = None
+    // index of the bucket a key belongs to, None if no buckets have been allocated yet
+    fn position<Q : Hash + ?Sized>(&self, key : &Q) -> Option<usize> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+        Some((self.hash_builder.hash_one(key) % self.buckets.len() as u64) as usize)
+    }
+
+    // moves every node into a new set of buckets
+    // the nodes are relinked rather than cloned
+    fn resize(&mut self, bucket_count : usize) {
+        let old_buckets = std::mem::replace(&mut self.buckets, empty_buckets(bucket_count));
+        for mut chain in old_buckets {
+            while let Some(mut node) = chain {
+                chain = node.next.take();
+                let position = self.position(&node.key).unwrap();
+                node.next = self.buckets[position].take();
+                self.buckets[position] = Some(node);
             }
+        }
+    }
+}
+
+// a view into a single entry of the map, which may either be vacant or occupied
+pub enum Entry<'a, T, V, S = RandomState> {
+    Occupied(OccupiedEntry<'a, T, V, S>),
+    Vacant(VacantEntry<'a, T, V, S>),
+}
+
+pub struct OccupiedEntry<'a, T, V, S = RandomState> {
+    map : &'a mut CustomHashMap<T,V,S>,
+    key : T,
+}
+
+pub struct VacantEntry<'a, T, V, S = RandomState> {
+    map : &'a mut CustomHashMap<T,V,S>,
+    key : T,
+}
+
+impl<'a, T : Hash + Eq, V, S : BuildHasher> Entry<'a, T, V, S> {
+    pub fn key(&self) -> &T {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    // inserts the default value if the entry is vacant, returning a mutable reference to the value
+    pub fn or_insert(self, default : V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    // same as or_insert, the value is only computed if the entry is vacant
+    pub fn or_insert_with<F : FnOnce() -> V>(self, default : F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V : Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    // modifies the value in place if the entry is occupied
+    pub fn and_modify<F : FnOnce(&mut V)>(mut self, modify : F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            modify(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T : Hash + Eq, V, S : BuildHasher> OccupiedEntry<'a, T, V, S> {
+    pub fn key(&self) -> &T {
+        &self.key
+    }
+
+    pub fn get(&self) -> &V {
+        self.map.get(&self.key).unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.get_mut(&self.key).unwrap()
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.get_mut(&self.key).unwrap()
+    }
+
+    // replaces the value, returning the old one
+    pub fn insert(&mut self, value : V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.map.remove(&self.key).unwrap()
+    }
+}
+
+impl<'a, T : Hash + Eq, V, S : BuildHasher> VacantEntry<'a, T, V, S> {
+    pub fn key(&self) -> &T {
+        &self.key
+    }
+
+    pub fn into_key(self) -> T {
+        self.key
+    }
+
+    pub fn insert(self, value : V) -> &'a mut V {
+        self.map.insert_new(self.key, value)
+    }
+}
+
+// iterator over shared references to the key value pairs
+// walks every bucket, and every node of the bucket's linked list
+pub struct Iter<'a, T, V> {
+    buckets : std::slice::Iter<'a, Bucket<T,V>>,
+    current : Option<&'a KeyValue<T,V>>,
+    remaining : usize,
+}
 
-            // return the value the node held
-            self.curr_size -= 1;
-            return Some(return_val);
+impl<'a, T, V> Iterator for Iter<'a, T, V> {
+    type Item = (&'a T, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.current {
+                self.current = node.next.as_deref();
+                self.remaining -= 1;
+                return Some((&node.key, &node.value));
+            }
+            self.current = self.buckets.next()?.as_deref();
         }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, V> ExactSizeIterator for Iter<'_, T, V> {}
+
+// iterator over the key value pairs, with mutable references to the values
+pub struct IterMut<'a, T, V> {
+    buckets : std::slice::IterMut<'a, Bucket<T,V>>,
+    current : Option<&'a mut KeyValue<T,V>>,
+    remaining : usize,
+}
 
-        // iterate through until key is found
-        // 
-        // The following steps are being performed within the code:
-        // 1. we check if the next KeyValue is not None.
-        // 2. if it isn't None, we check if it's key matches the key we're looking for.
-        // 3. if it does, we store the return value to return later.
-        // 4. we then check if the next KeyValue after this is also a value.
-        // 5. if it is, we set our current value to point to this "next next" value.
-        // 6. if there's no "next next" value, we set our current value to point to None.
-        // 7. we then decrement the current size and return the value.
-        while current.next.is_some() {
-            let next = current.next.as_mut().unwrap();
-
-            // if the key has been found
-            if next.key == key {
-
-                // set the value
-                let return_val = next.value;
-
-                // check if there's a next val
-                // if there is next, update array to point ot this val
-                if let Some(next_next) = next.next.to_owned() {
-                    current.next = Some(Box::new(*next_next));
-                } else {
-                    current.next = None         // otherwise, if next doesn't exist, have it point to None
-                }
-
-                // return the value the node held
-                // shrink the size of the linked list by 1 as well
-                self.curr_size -= 1;
-                return Some(return_val);
+impl<'a, T, V> Iterator for IterMut<'a, T, V> {
+    type Item = (&'a T, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.current.take() {
+                // the fields are borrowed separately, so the next node can be stored while the value is handed out
+                let KeyValue { key, value, next } = node;
+                self.current = next.as_deref_mut();
+                self.remaining -= 1;
+                return Some((&*key, value));
             }
+            self.current = self.buckets.next()?.as_deref_mut();
+        }
+    }
 
-            // set current equal to the next
-            current = current.next.as_mut().unwrap();
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, V> ExactSizeIterator for IterMut<'_, T, V> {}
+
+// iterator that moves the key value pairs out of the map
+pub struct IntoIter<T, V> {
+    buckets : std::vec::IntoIter<Bucket<T,V>>,
+    current : Bucket<T,V>,
+    remaining : usize,
+}
+
+impl<T, V> Iterator for IntoIter<T, V> {
+    type Item = (T, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(mut node) = self.current.take() {
+                self.current = node.next.take();
+                self.remaining -= 1;
+                return Some((node.key, node.value));
+            }
+            self.current = self.buckets.next()?;
         }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
 
+impl<T, V> ExactSizeIterator for IntoIter<T, V> {}
 
-        // if we reach the end of the Linked list with no value, simply return None
-        None
+impl<T, V, S> IntoIterator for CustomHashMap<T,V,S> {
+    type Item = (T, V);
+    type IntoIter = IntoIter<T, V>;
 
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            buckets : self.buckets.into_iter(),
+            current : None,
+            remaining : self.curr_size,
+        }
     }
+}
 
-    // clears the hashmap
-    // similar to the drain method within the std::collections::CustomHashMap
-    //
-    // overwrite the existing array to remove everything
-    // the definition is essentially similar to the constructor method
-    pub fn clear(&mut self) {
-        self.curr_size = 0;        
-        self.arr = [Self::INIT; DEFAULT_MAX_SIZE as usize];        
+impl<'a, T : Hash + Eq, V, S : BuildHasher> IntoIterator for &'a CustomHashMap<T,V,S> {
+    type Item = (&'a T, &'a V);
+    type IntoIter = Iter<'a, T, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
+}
 
-    pub fn len(&self) -> usize {
-        self.curr_size
+impl<'a, T : Hash + Eq, V, S : BuildHasher> IntoIterator for &'a mut CustomHashMap<T,V,S> {
+    type Item = (&'a T, &'a mut V);
+    type IntoIter = IterMut<'a, T, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T : Hash + Eq, V, S : BuildHasher + Default> FromIterator<(T, V)> for CustomHashMap<T,V,S> {
+    fn from_iter<I : IntoIterator<Item = (T, V)>>(iter : I) -> Self {
+        let mut map = CustomHashMap::with_hasher(S::default());
+        map.extend(iter);
+        map
     }
+}
 
-    pub fn is_empty(&self) -> bool {
-        if self.len() == 0 {
-            return true;
+impl<T : Hash + Eq, V, S : BuildHasher> Extend<(T, V)> for CustomHashMap<T,V,S> {
+    fn extend<I : IntoIterator<Item = (T, V)>>(&mut self, iter : I) {
+        for (key, value) in iter {
+            self.insert(key, value);
         }
-        false
     }
 }
 
-// hash_key implementation
-// .hash() method comes from the Hash trait that has been attached to generic type T
-fn hash_key<T : Hash>(key : T) -> u64 {
-    let mut hasher = DefaultHasher::new();
-    key.hash(&mut hasher);
-    let hash_val = hasher.finish();
-    hash_val
+impl<T : Hash + Eq, V, S : BuildHasher + Default> Default for CustomHashMap<T,V,S> {
+    fn default() -> Self {
+        CustomHashMap::with_hasher(S::default())
+    }
+}
+
+// smallest power of two number of buckets that can hold `capacity` elements within the load factor
+fn bucket_count_for(capacity : usize) -> usize {
+    let required = capacity.saturating_mul(MAX_LOAD_FACTOR_DENOMINATOR).div_ceil(MAX_LOAD_FACTOR_NUMERATOR);
+    required.max(INITIAL_BUCKET_COUNT).next_power_of_two()
+}
+
+fn empty_buckets<T,V>(bucket_count : usize) -> Vec<Bucket<T,V>> {
+    std::iter::repeat_with(|| None).take(bucket_count).collect()
+}
+
+// FNV-1a hasher
+// much cheaper than SipHash for the short keys hashed by the crate's internal indexes (i.e. "CSC 21200")
+// but it's not seeded, so it shouldn't be used for maps keyed by untrusted input
+#[derive(Debug, Clone, Copy)]
+pub struct FnvHasher(u64);
+
+const FNV_OFFSET_BASIS : u64 = 0xcbf29ce484222325;
+const FNV_PRIME : u64 = 0x100000001b3;
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes : &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
 }
 
+// BuildHasher creating FnvHasher instances, (i.e. CustomHashMap::with_hasher(FnvBuildHasher::default()))
+pub type FnvBuildHasher = BuildHasherDefault<FnvHasher>;
+
 
 // we can run tests to check if the hashmap works
 // tested within rust playground
@@ -339,21 +613,111 @@ fn test_can_get_item() {
     let mut my_hash : CustomHashMap<String, i32> = CustomHashMap::new();
     my_hash.insert(key.clone(), value);
 
-    let result = my_hash.get(key).unwrap();
-    assert_eq!(result,value)
+    let result = my_hash.get(&key).unwrap();
+    assert_eq!(*result,value)
+}
+
+#[test]
+fn test_grows_past_initial_buckets() {
+    // a whole catalog worth of keys, far more than the initial number of buckets
+    let mut my_hash : CustomHashMap<u32, u32> = CustomHashMap::new();
+    for key in 0..5000 {
+        assert_eq!(my_hash.insert(key, key * 2), None);
+    }
+
+    assert_eq!(my_hash.len(), 5000);
+    assert!(my_hash.capacity() >= 5000);
+    assert!((0..5000).all(|key| my_hash.get(&key) == Some(&(key * 2))));
 }
 
+#[test]
+fn test_insert_existing_and_remove() {
+    let mut my_hash : CustomHashMap<&str, i32> = CustomHashMap::with_capacity(4);
+    assert_eq!(my_hash.insert("CSC 21200", 3), None);
+    assert_eq!(my_hash.insert("CSC 21200", 4), Some(3));
+    assert_eq!(my_hash.len(), 1);
+
+    assert_eq!(my_hash.remove(&"CSC 21200"), Some(4));
+    assert_eq!(my_hash.remove(&"CSC 21200"), None);
+    assert!(my_hash.is_empty());
+}
+
+#[test]
+fn test_with_capacity_does_not_resize() {
+    let mut my_hash : CustomHashMap<usize, usize> = CustomHashMap::with_capacity(100);
+    let capacity = my_hash.capacity();
+    for key in 0..100 {
+        my_hash.insert(key, key);
+    }
+    assert_eq!(my_hash.capacity(), capacity);
+
+    my_hash.clear();
+    assert_eq!(my_hash.get(&1), None);
+    assert_eq!(my_hash.capacity(), capacity);
+}
+
+#[test]
+fn test_borrowed_lookups_and_non_copy_values() {
+    let mut my_hash : CustomHashMap<String, Vec<String>> = CustomHashMap::new();
+    my_hash.insert("CSC-CTY".to_string(), vec!["CSC 21200".to_string()]);
+
+    // String keys can be looked up with a &str
+    my_hash.get_mut("CSC-CTY").unwrap().push("CSC 22000".to_string());
+    assert_eq!(my_hash.get("CSC-CTY").map(Vec::len), Some(2));
+    assert!(my_hash.contains_key("CSC-CTY"));
+    assert_eq!(my_hash.remove_entry("CSC-CTY").map(|(key, _)| key), Some("CSC-CTY".to_string()));
+}
+
+#[test]
+fn test_iterators() {
+    let mut my_hash : CustomHashMap<u32, u32> = (0..100).map(|key| (key, key)).collect();
+    assert_eq!(my_hash.iter().len(), 100);
+
+    for (_, value) in my_hash.iter_mut() {
+        *value *= 10;
+    }
+    let mut keys : Vec<u32> = my_hash.keys().copied().collect();
+    keys.sort();
+    assert_eq!(keys, (0..100).collect::<Vec<u32>>());
+    assert_eq!(my_hash.values().sum::<u32>(), 49500);
+
+    my_hash.extend([(100, 1000), (0, 1)]);
+    assert_eq!(my_hash.len(), 101);
+    let mut pairs : Vec<(u32, u32)> = my_hash.into_iter().collect();
+    pairs.sort();
+    assert_eq!(pairs[0], (0, 1));
+    assert_eq!(pairs[100], (100, 1000));
+}
 
-// Tested in rust playground
-// fn main() {
-//     let mut my_hashmap : CustomHashMap<i32,i32> = CustomHashMap::new();
-//     let key = 1;
-//     let val = 10;
-    
-//     my_hashmap.insert(key.clone(), val);
-//     // println!("{:?}", my_hashmap.get(key).unwrap());
-    
-//     my_hashmap.clear();
-//     println!("{:?}", my_hashmap);
-    
-// }
\ No newline at end of file
+#[test]
+fn test_entry_api() {
+    let mut course_counts : CustomHashMap<&str, u32> = CustomHashMap::new();
+    for subject_code in ["CSC", "EE", "CSC", "CSC"] {
+        *course_counts.entry(subject_code).or_insert(0) += 1;
+    }
+    assert_eq!(course_counts.get("CSC"), Some(&3));
+
+    course_counts.entry("EE").and_modify(|count| *count += 10).or_default();
+    course_counts.entry("MATH").and_modify(|count| *count += 10).or_default();
+    assert_eq!(course_counts.get("EE"), Some(&11));
+    assert_eq!(course_counts.get("MATH"), Some(&0));
+
+    if let Entry::Occupied(entry) = course_counts.entry("CSC") {
+        assert_eq!(entry.remove(), 3);
+    }
+    assert!(!course_counts.contains_key("CSC"));
+}
+
+#[test]
+fn test_custom_hasher() {
+    let mut course_index : CustomHashMap<&str, &str, FnvBuildHasher> = CustomHashMap::with_capacity_and_hasher(2, FnvBuildHasher::default());
+    course_index.insert("CSC 21200", "Data Structures");
+    course_index.insert("CSC 22000", "Algorithms");
+    assert_eq!(course_index.get("CSC 22000"), Some(&"Algorithms"));
+
+    // FNV is deterministic, the same key always hashes to the same value
+    assert_eq!(course_index.hasher().hash_one("CSC 21200"), FnvBuildHasher::default().hash_one("CSC 21200"));
+
+    let collected : CustomHashMap<u32, u32, FnvBuildHasher> = (0..50).map(|key| (key, key)).collect();
+    assert_eq!(collected.len(), 50);
+}
@@ -6,19 +6,116 @@
 // operates on the principle that the data most recently accessed is likely to be accesses again in the near future.
 
 use crate::data_structures::custom_hashmap::CustomHashMap;
+use std::fmt::Debug;
 use std::hash::Hash;
-use std::mem;
-use std::marker::Copy;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// source of the current time used to decide if an entry has expired
+// injectable so that expiration can be tested without sleeping (refer to ManualClock)
+pub trait Clock : Debug + Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+// the clock used by default, simply reads the system's monotonic clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// clock that only moves when advance() is called
+// clones share the same time, so a clone can be kept around to advance the clock handed to the cache
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now : Arc<Mutex<Instant>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            now : Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    pub fn advance(&self, duration : Duration) {
+        *self.now.lock().expect("manual clock lock poisoned") += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().expect("manual clock lock poisoned")
+    }
+}
+
+// counters describing how the cache has been used, useful for tuning it's capacity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits : u64,             // lookups that found a live entry
+    pub misses : u64,           // lookups that found nothing (or an expired entry)
+    pub inserts : u64,          // insertions, including ones replacing an existing value
+    pub evictions : u64,        // entries removed to stay within capacity
+    pub expirations : u64,      // entries removed because their time to live ran out
+}
+
+// callback invoked with every entry evicted to stay within capacity
+// wrapped so the cache can still implement Debug and Clone (clones share the same callback)
+pub struct EvictionListener<K, V>(Arc<EvictionCallback<K, V>>);
+
+type EvictionCallback<K, V> = dyn Fn(&K, &V) + Send + Sync;
+
+// function estimating the weight of an entry (i.e. it's approximate size in bytes)
+// wrapped for the same reason as EvictionListener
+pub struct Weigher<K, V>(Arc<WeighCallback<K, V>>);
+
+type WeighCallback<K, V> = dyn Fn(&K, &V) -> usize + Send + Sync;
+
+impl<K, V> Clone for Weigher<K, V> {
+    fn clone(&self) -> Self {
+        Weigher(Arc::clone(&self.0))
+    }
+}
+
+impl<K, V> Debug for Weigher<K, V> {
+    fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Weigher")
+    }
+}
+
+impl<K, V> Clone for EvictionListener<K, V> {
+    fn clone(&self) -> Self {
+        EvictionListener(Arc::clone(&self.0))
+    }
+}
+
+impl<K, V> Debug for EvictionListener<K, V> {
+    fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EvictionListener")
+    }
+}
 
 // struct of double linked list
 // since LRU Cache uses Double Linked List under the hood
+// values are owned by the nodes, so they don't need to implement Copy (i.e. Vec<CourseInfo> or String)
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug)]
 pub struct Node<K,V> {
     pub key : K,
     pub value : V,
     pub prev : Option<usize>,            // prev pointer
     pub next : Option<usize>,            // next pointer
+    pub expires_at : Option<Instant>,    // None if the entry never expires
+    pub weight : usize,                  // weight of the entry according to the weigher, 0 without one
 }
 
 // LRU Cache implementation using custom_hashmap and double linked list
@@ -26,50 +123,129 @@ pub struct Node<K,V> {
 pub struct CustomLruCache<K, V> {
 
     // Hashmap stores key to node index mapping
-    // map : custom_hashmap::CustomHashMap<K, usize>,
-    pub map : CustomHashMap<K, usize>,
+    map : CustomHashMap<K, usize>,
 
     // vector stores nodes based on index
-    pub nodes : Vec<Option<Node<K,V>>>,
+    nodes : Vec<Option<Node<K,V>>>,
 
     // Free list for node reuse
-    pub free : Vec<usize>,
+    free : Vec<usize>,
 
     // Head node of Double Linked List
-    pub head : Option<usize>,
+    head : Option<usize>,
 
     // Tail node of Double Linked List
-    pub tail : Option<usize>,
+    tail : Option<usize>,
 
     // maximum capacity
-    pub capacity : usize,
+    capacity : usize,
+
+    // time to live applied by insert(), None means entries only leave the cache through eviction
+    default_ttl : Option<Duration>,
+
+    clock : Arc<dyn Clock>,
+
+    stats : CacheStats,
+
+    eviction_listener : Option<EvictionListener<K, V>>,
+
+    // optional bound on the total weight of the entries, enforced along with the capacity
+    weigher : Option<Weigher<K, V>>,
+    max_weight : usize,
+    total_weight : usize,
 }
 
 // Attach traits to generic types
-impl<K : Hash + Eq + Clone, V : Copy> CustomLruCache<K,V> {
+impl<K : Hash + Eq + Clone, V> CustomLruCache<K,V> {
     // create an LRU Cache based on the capacity provided within the parameter
     // constructor
     pub fn new(capacity : usize) -> Self {
+        CustomLruCache::with_ttl_and_clock(capacity, None, Arc::new(SystemClock))
+    }
+
+    // every entry inserted through insert() expires once it's older than ttl
+    pub fn with_ttl(capacity : usize, ttl : Duration) -> Self {
+        CustomLruCache::with_ttl_and_clock(capacity, Some(ttl), Arc::new(SystemClock))
+    }
+
+    // same as with_ttl, reading the current time from the provided clock
+    pub fn with_ttl_and_clock(capacity : usize, default_ttl : Option<Duration>, clock : Arc<dyn Clock>) -> Self {
         CustomLruCache {
 
-            // map can use the original hashmap as well
-            // std::collections::HashMap::with_capacity(capacity) is also another possible choice
-            map : CustomHashMap::new(),         // since I did not implement a prebuilt capacity based hashmap
+            // the map grows along with the number of cached items
+            // so a large capacity doesn't allocate buckets that may never be used
+            map : CustomHashMap::new(),
             nodes : Vec::with_capacity(capacity),
             free : Vec::new(),
             head : None,
             tail : None,
-            capacity
+            capacity,
+            default_ttl,
+            clock,
+            stats : CacheStats::default(),
+            eviction_listener : None,
+            weigher : None,
+            max_weight : usize::MAX,
+            total_weight : 0,
         }
     }
 
+    // bounds the cache by the total weight of it's entries on top of the number of entries
+    // i.e. weighing entries by their approximate size in bytes, so that one large department listing
+    // takes up as much of the cache as many small course details
+    //
+    // entries already within the cache are re-weighed, returns the entries evicted to fit within max_weight
+    pub fn set_weigher<F>(&mut self, max_weight : usize, weigher : F) -> Vec<(K, V)>
+    where
+        F : Fn(&K, &V) -> usize + Send + Sync + 'static,
+    {
+        self.total_weight = 0;
+        for node in self.nodes.iter_mut().flatten() {
+            node.weight = weigher(&node.key, &node.value);
+            self.total_weight = self.total_weight.saturating_add(node.weight);
+        }
+
+        self.weigher = Some(Weigher(Arc::new(weigher)));
+        self.max_weight = max_weight;
+        self.evict_to_fit()
+    }
+
+    // total weight of the entries currently within the cache
+    pub fn total_weight(&self) -> usize {
+        self.total_weight
+    }
+
+    // maximum total weight, usize::MAX unless a weigher was set
+    pub fn max_weight(&self) -> usize {
+        self.max_weight
+    }
+
+    // registers a callback invoked with every entry evicted to stay within capacity (i.e. to spill it to disk)
+    // entries that are removed, replaced or expired are not passed to the listener
+    pub fn set_eviction_listener<F>(&mut self, listener : F)
+    where
+        F : Fn(&K, &V) + Send + Sync + 'static,
+    {
+        self.eviction_listener = Some(EvictionListener(Arc::new(listener)));
+    }
+
+    // snapshot of the counters collected since the cache was created (or since reset_stats was called)
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
     // get value by, moving accessed item by front
     // returns an immutable reference, meaning we simply want the value 
     // without the intention of making any in-place modification to the cache
+    // expired entries are removed instead of being returned
     pub fn get(&mut self, key : &K) -> Option<&V> {
 
         // get node index from map
-        let node_idx = self.map.get(key.clone())?;     // using ? could cause error
+        let node_idx = self.live_index(key)?;
 
         // Move node to the front of the list (since it was just accessed and likely to be accessed again)
         // logic of prepending a node to a double linked list
@@ -83,21 +259,105 @@ impl<K : Hash + Eq + Clone, V : Copy> CustomLruCache<K,V> {
     // get mutable reference to value
     // this method should execute with the intention of modifying the reference
     pub fn get_mut(&mut self, key : &K) -> Option<&mut V> {
-        let node_idx = self.map.get(key.clone())?;
+        let node_idx = self.live_index(key)?;
         self.move_to_front(node_idx);
         self.nodes[node_idx].as_mut().map(|node| &mut node.value)
     }
 
+    // get value without marking it as recently used
+    // since the recency isn't updated, only a shared reference to the cache is needed
+    // which also means expired entries are skipped rather than removed
+    pub fn peek(&self, key : &K) -> Option<&V> {
+        let node_idx = self.map.get(key).copied()?;
+        let now = self.clock.now();
+        self.nodes[node_idx].as_ref()
+            .filter(|node| !is_expired(node, now))
+            .map(|node| &node.value)
+    }
+
+    // check if a key is present and hasn't expired, without marking it as recently used
+    pub fn contains_key(&self, key : &K) -> bool {
+        self.peek(key).is_some()
+    }
+
     // insert key-value pair
     // evict least recently used if at capacity
-    pub fn insert(&mut self, key : K, value : V) {
+    //
+    // returns the entry that is no longer within the cache, meaning either
+    // the previous value of the key (if the key already existed) or the evicted least recently used entry
+    // with a weigher, a heavy entry may evict more than one entry, every evicted entry is passed to the eviction listener
+    // (refer to insert_weighted to get all of them back instead)
+    pub fn insert(&mut self, key : K, value : V) -> Option<(K, V)> {
+        let expires_at = self.expiry(self.default_ttl);
+        let weight = self.weigh(&key, &value);
+        self.insert_with_expiry(key, value, expires_at, weight).into_iter().next()
+    }
+
+    // same as insert, but the entry expires after the provided ttl instead of the cache's default ttl
+    pub fn insert_with_ttl(&mut self, key : K, value : V, ttl : Duration) -> Option<(K, V)> {
+        let expires_at = self.expiry(Some(ttl));
+        let weight = self.weigh(&key, &value);
+        self.insert_with_expiry(key, value, expires_at, weight).into_iter().next()
+    }
+
+    // same as insert, using the provided weight instead of the weigher's
+    // i.e. so that an expensive weight can be computed before locking a shared cache
+    //
+    // returns every entry that is no longer within the cache, meaning
+    // the previous value of the key (if the key already existed) followed by the evicted least recently used entries,
+    // an entry heavier than the maximum weight evicts everything, including itself
+    pub fn insert_weighted(&mut self, key : K, value : V, weight : usize) -> Vec<(K, V)> {
+        let expires_at = self.expiry(self.default_ttl);
+        self.insert_with_expiry(key, value, expires_at, weight)
+    }
+
+    // removes every expired entry, returning how many were removed
+    // expired entries are otherwise only removed once they are looked up (or evicted)
+    pub fn purge_expired(&mut self) -> usize {
+        let now = self.clock.now();
+        let expired : Vec<usize> = self.nodes.iter()
+            .enumerate()
+            .filter(|(_, node)| node.as_ref().is_some_and(|node| is_expired(node, now)))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        for node_idx in &expired {
+            if let Some((key, _)) = self.remove_node(*node_idx) {
+                self.map.remove(&key);
+            }
+        }
+
+        self.stats.expirations += expired.len() as u64;
+        expired.len()
+    }
+
+    // get current number of items within cache
+    // expired entries that haven't been removed yet are included
+    pub fn len(&self) -> usize {
+        self.map.len()          
+    }
+
+    // time to live applied by insert()
+    pub fn ttl(&self) -> Option<Duration> {
+        self.default_ttl
+    }
+
+    fn insert_with_expiry(&mut self, key : K, value : V, expires_at : Option<Instant>, weight : usize) -> Vec<(K, V)> {
+        self.stats.inserts += 1;
 
         // If key exists, update value and move to front
-        if let Some(node_idx) = self.map.get(key.clone()) {
+        if let Some(node_idx) = self.map.get(&key).copied() {
             if let Some(node) = self.nodes[node_idx].as_mut() {
-                node.value = value;         // update the value wtihin the existing node
+                node.expires_at = expires_at;          // the replaced value starts a new time to live
+                self.total_weight = self.total_weight.saturating_sub(node.weight).saturating_add(weight);
+                node.weight = weight;
+                let old_value = std::mem::replace(&mut node.value, value);         // update the value wtihin the existing node
                 self.move_to_front(node_idx);           // remove and prepend the node
-                return;
+
+                // the new value may be heavier than the old one
+                let mut removed = vec![(key, old_value)];
+                removed.extend(self.evict_to_fit());
+                return removed;
             }
         }
 
@@ -118,6 +378,8 @@ impl<K : Hash + Eq + Clone, V : Copy> CustomLruCache<K,V> {
             value,
             prev : None,            // because this would be the first node
             next : self.head,       // beause the node will be prepended, the next node will be come the old head node
+            expires_at,
+            weight,
         };
 
         // update head and tail element
@@ -134,17 +396,16 @@ impl<K : Hash + Eq + Clone, V : Copy> CustomLruCache<K,V> {
         // nodes array stores double linked list as elements
         self.nodes[node_idx] = Some(new_node);
         self.map.insert(key, node_idx);         // hashmap stores the key and the index of nodes array where the double linked list node can be found 
+        self.total_weight = self.total_weight.saturating_add(weight);
 
         // check capacity and evict as needed
-        if self.map.len() > self.capacity {
-            self.evict_tail();          // evict_tail() : private helper method
-        }
+        self.evict_to_fit()
     }
 
     // remove item from cache
     pub fn remove(&mut self, key : &K) -> Option<V> {
-        let node_idx = self.map.remove(key.clone())?;
-        self.remove_node(node_idx)     // remove_node() : private helper method
+        let node_idx = self.map.remove(key)?;
+        self.remove_node(node_idx).map(|(_, value)| value)     // remove_node() : private helper method
     }
 
     // clear the cache, removing all items
@@ -155,21 +416,54 @@ impl<K : Hash + Eq + Clone, V : Copy> CustomLruCache<K,V> {
         self.map.clear();           
         self.nodes.clear();
         self.free.clear();
+        self.total_weight = 0;
         self.head = None;
         self.tail = None;
     }
 
-    // get current number of items within cache
-    pub fn len(&self) -> usize {
-        self.map.len()          
-    }
-
     // check if the cache is empty
     pub fn is_empty(&self) -> bool {
         self.map.is_empty()         
     }
 
+    // maximum number of items the cache can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     // below are the set of private helper methods that are being used by the above methods
+
+    // time at which an entry inserted now expires
+    // a ttl too large to be represented (i.e. Duration::MAX) means the entry never expires
+    fn expiry(&self, ttl : Option<Duration>) -> Option<Instant> {
+        ttl.and_then(|ttl| self.clock.now().checked_add(ttl))
+    }
+
+    // weight of the entry according to the weigher, 0 without one
+    fn weigh(&self, key : &K, value : &V) -> usize {
+        self.weigher.as_ref().map_or(0, |weigher| (weigher.0)(key, value))
+    }
+
+    // node index of the key, removing the entry if it has expired
+    // also records the lookup as a hit or a miss
+    fn live_index(&mut self, key : &K) -> Option<usize> {
+        let Some(node_idx) = self.map.get(key).copied() else {
+            self.stats.misses += 1;
+            return None;
+        };
+
+        let now = self.clock.now();
+        if self.nodes[node_idx].as_ref().is_some_and(|node| is_expired(node, now)) {
+            self.map.remove(key);
+            self.remove_node(node_idx);
+            self.stats.misses += 1;
+            self.stats.expirations += 1;
+            return None;
+        }
+
+        self.stats.hits += 1;
+        Some(node_idx)
+    }
     
     // removes the node from the current position of the linked list
     // and move the node to the front of the linked list
@@ -181,36 +475,37 @@ impl<K : Hash + Eq + Clone, V : Copy> CustomLruCache<K,V> {
             return;
         }
 
-        let mut binding = self.nodes[node_idx].clone();
-        let node = binding.as_mut().unwrap();
+        let node = self.nodes[node_idx].as_ref().unwrap();
+        let (prev, next) = (node.prev, node.next);
 
         // update adjacent nodes
-        if let Some(prev) = node.clone().prev {
-            self.nodes[prev].clone().as_mut().unwrap().next = node.next;
+        if let Some(prev) = prev {
+            self.nodes[prev].as_mut().unwrap().next = next;
         }
 
-        if let Some(next) = node.next {
-            self.nodes[next].clone().as_mut().unwrap().prev = node.prev;
+        if let Some(next) = next {
+            self.nodes[next].as_mut().unwrap().prev = prev;
         }
 
         // update tail if moving tail
         if Some(node_idx) == self.tail {
-            self.tail = node.prev;
+            self.tail = prev;
         }
 
         // move to front
-        node.prev = None;
-        node.next = self.head;
         if let Some(head) = self.head {
             self.nodes[head].as_mut().unwrap().prev = Some(node_idx);
         }
 
+        let node = self.nodes[node_idx].as_mut().unwrap();
+        node.prev = None;
+        node.next = self.head;
         self.head = Some(node_idx);
     }
 
-    // remove node and return it's value
+    // remove node and return it's key and value
     // Option::take() : to move ownership out and leave None on the place
-    fn remove_node(&mut self, node_idx : usize) -> Option<V> {
+    fn remove_node(&mut self, node_idx : usize) -> Option<(K, V)> {
         let node = self.nodes[node_idx].take()?;        
 
         // update adjacent nodes
@@ -233,21 +528,44 @@ impl<K : Hash + Eq + Clone, V : Copy> CustomLruCache<K,V> {
 
         // add index to free list
         self.free.push(node_idx);
+        self.total_weight = self.total_weight.saturating_sub(node.weight);
 
-        Some(node.value)
+        Some((node.key, node.value))
     }
 
-    // remove least recently used tail item
-    fn evict_tail(&mut self) {
-        if let Some(tail) = self.tail {
-            if let Some(node) = &self.nodes[tail] {
-                self.map.remove(node.key.clone());
-                self.remove_node(tail);
+    // evicts least recently used items until both the capacity and the maximum weight are respected
+    fn evict_to_fit(&mut self) -> Vec<(K, V)> {
+        let mut evicted = Vec::new();
+        while self.map.len() > self.capacity || self.total_weight > self.max_weight {
+            match self.evict_tail() {          // evict_tail() : private helper method
+                Some(entry) => evicted.push(entry),
+                None => break,
             }
         }
+
+        evicted
+    }
+
+    // remove least recently used tail item, notifying the eviction listener
+    fn evict_tail(&mut self) -> Option<(K, V)> {
+        let tail = self.tail?;
+        let key = self.nodes[tail].as_ref()?.key.clone();
+        self.map.remove(&key);
+        let evicted = self.remove_node(tail)?;
+
+        self.stats.evictions += 1;
+        if let Some(listener) = &self.eviction_listener {
+            (listener.0)(&evicted.0, &evicted.1);
+        }
+
+        Some(evicted)
     }
 }
 
+fn is_expired<K, V>(node : &Node<K,V>, now : Instant) -> bool {
+    node.expires_at.is_some_and(|expires_at| now >= expires_at)
+}
+
 
 // test the cache to make sure that it works properly as intended.
 #[cfg(test)]
@@ -280,6 +598,46 @@ mod tests {
         assert_eq!(cache.len(), 1);
     }
 
+    #[test]
+    fn test_recently_used_is_kept() {
+        let mut cache = CustomLruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        cache.insert(3, "three");
+        cache.get(&2);          // 2 becomes the most recently used item
+
+        cache.insert(4, "four");
+        assert_eq!(cache.get(&3), None); // least recently used, should be evicted
+        assert_eq!(cache.get(&2), Some(&"two"));
+        assert_eq!(cache.get(&4), Some(&"four"));
+    }
+
+    #[test]
+    fn test_owned_values() {
+        let mut cache : CustomLruCache<String, Vec<String>> = CustomLruCache::new(1);
+        assert_eq!(cache.insert("CSC-CTY".to_owned(), vec!["Data Structures".to_owned()]), None);
+        cache.get_mut(&"CSC-CTY".to_owned()).unwrap().push("Algorithms".to_owned());
+        assert_eq!(cache.peek(&"CSC-CTY".to_owned()).unwrap().len(), 2);
+
+        // the replaced entry is handed back, followed by the evicted one
+        let replaced = cache.insert("CSC-CTY".to_owned(), Vec::new());
+        assert_eq!(replaced, Some(("CSC-CTY".to_owned(), vec!["Data Structures".to_owned(), "Algorithms".to_owned()])));
+        let evicted = cache.insert("EE-CTY".to_owned(), vec!["Circuits".to_owned()]);
+        assert_eq!(evicted, Some(("CSC-CTY".to_owned(), Vec::new())));
+    }
+
+    #[test]
+    fn test_peek_does_not_update_recency() {
+        let mut cache = CustomLruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.peek(&1), Some(&"one"));
+
+        // 1 is still the least recently used item
+        assert_eq!(cache.insert(3, "three"), Some((1, "one")));
+        assert!(!cache.contains_key(&1));
+    }
+
     #[test]
     fn test_clear() {
         let mut cache = CustomLruCache::new(2);
@@ -289,4 +647,159 @@ mod tests {
         assert!(cache.is_empty());
         assert_eq!(cache.get(&1), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let clock = ManualClock::new();
+        let mut cache = CustomLruCache::with_ttl_and_clock(4, Some(Duration::from_secs(60)), Arc::new(clock.clone()));
+        cache.insert(1, "one");
+        cache.insert_with_ttl(2, "two", Duration::from_secs(120));
+
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(cache.get(&1), Some(&"one"));
+
+        // 1 has expired, 2 is still alive because of it's longer ttl
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(cache.peek(&1), None);
+        assert!(!cache.contains_key(&1));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&2), Some(&"two"));
+
+        // replacing a value restarts it's ttl
+        clock.advance(Duration::from_secs(30));
+        cache.insert(2, "TWO");
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(cache.get(&2), Some(&"TWO"));
+    }
+
+    #[test]
+    fn test_unrepresentable_ttl_never_expires() {
+        let clock = ManualClock::new();
+        let mut cache = CustomLruCache::with_ttl_and_clock(4, Some(Duration::MAX), Arc::new(clock.clone()));
+        cache.insert(1, "one");
+        cache.insert_with_ttl(2, "two", Duration::MAX);
+        cache.insert_weighted(3, "three", 0);
+
+        clock.advance(Duration::from_secs(365 * 24 * 60 * 60));
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.get(&2), Some(&"two"));
+        assert_eq!(cache.get(&3), Some(&"three"));
+        assert_eq!(cache.purge_expired(), 0);
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let clock = ManualClock::new();
+        let mut cache = CustomLruCache::with_ttl_and_clock(4, Some(Duration::from_secs(10)), Arc::new(clock.clone()));
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        cache.insert_with_ttl(3, "three", Duration::from_secs(20));
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(cache.purge_expired(), 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&3), Some(&"three"));
+
+        // the freed nodes are reused by later insertions
+        cache.insert(4, "four");
+        assert_eq!(cache.get(&4), Some(&"four"));
+        assert_eq!(cache.purge_expired(), 0);
+    }
+
+    #[test]
+    fn test_stats() {
+        let clock = ManualClock::new();
+        let mut cache = CustomLruCache::with_ttl_and_clock(2, Some(Duration::from_secs(10)), Arc::new(clock.clone()));
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        cache.insert(2, "TWO");
+        cache.get(&1);
+        cache.get(&5);
+        cache.insert(3, "three");      // evicts 2
+
+        clock.advance(Duration::from_secs(10));
+        cache.get(&1);
+
+        let stats = cache.stats();
+        assert_eq!(stats, CacheStats { hits : 1, misses : 2, inserts : 4, evictions : 1, expirations : 1 });
+
+        cache.reset_stats();
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn test_eviction_listener() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache = CustomLruCache::new(1);
+        let sink = Arc::clone(&evicted);
+        cache.set_eviction_listener(move |key : &u32, value : &&str| sink.lock().unwrap().push((*key, *value)));
+
+        cache.insert(1, "one");
+        cache.insert(1, "ONE");         // replaced, not evicted
+        cache.insert(2, "two");
+        cache.remove(&2);               // removed, not evicted
+        cache.insert(3, "three");
+        cache.insert(4, "four");
+
+        assert_eq!(*evicted.lock().unwrap(), vec![(1, "ONE"), (3, "three")]);
+    }
+
+    #[test]
+    fn test_weight_bounded_capacity() {
+        let mut cache : CustomLruCache<&str, String> = CustomLruCache::new(100);
+        cache.set_weigher(10, |_, value : &String| value.len());
+
+        cache.insert("a", "aaaa".to_owned());
+        cache.insert("b", "bbbb".to_owned());
+        assert_eq!(cache.total_weight(), 8);
+
+        // evicts both older entries to make room for the heavier one
+        let evicted = cache.insert_weighted("c", "cccccccc".to_owned(), 8);
+        assert_eq!(evicted, vec![("a", "aaaa".to_owned()), ("b", "bbbb".to_owned())]);
+        assert_eq!(cache.total_weight(), 8);
+
+        // growing an existing value also evicts
+        cache.insert("d", "dd".to_owned());
+        let removed = cache.insert_weighted("d", "ddd".to_owned(), 3);
+        assert_eq!(removed, vec![("d", "dd".to_owned()), ("c", "cccccccc".to_owned())]);
+        assert_eq!(cache.total_weight(), 3);
+
+        // an entry heavier than the maximum weight isn't kept
+        let evicted = cache.insert_weighted("e", "e".repeat(11), 11);
+        assert_eq!(evicted.len(), 2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.total_weight(), 0);
+    }
+
+    #[test]
+    fn test_weight_based_evictions_reach_the_listener() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache : CustomLruCache<&str, String> = CustomLruCache::new(100);
+        cache.set_weigher(10, |_, value : &String| value.len());
+        let sink = Arc::clone(&evicted);
+        cache.set_eviction_listener(move |key : &&str, _ : &String| sink.lock().unwrap().push(*key));
+
+        cache.insert("a", "aaaa".to_owned());
+        cache.insert("b", "bbbb".to_owned());
+
+        // insert only hands back the least recently used entry, the listener sees every evicted entry
+        assert_eq!(cache.insert("c", "cccccccc".to_owned()), Some(("a", "aaaa".to_owned())));
+        assert_eq!(*evicted.lock().unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_set_weigher_reweighs_existing_entries() {
+        let mut cache = CustomLruCache::new(10);
+        cache.insert(1, vec![0u8; 6]);
+        cache.insert(2, vec![0u8; 6]);
+        cache.remove(&2);
+        cache.insert(3, vec![0u8; 6]);
+
+        let evicted = cache.set_weigher(8, |_, value : &Vec<u8>| value.len());
+        assert_eq!(evicted, vec![(1, vec![0u8; 6])]);
+        assert_eq!(cache.total_weight(), 6);
+        assert_eq!(cache.max_weight(), 8);
+    }
+}
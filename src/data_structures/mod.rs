@@ -1,2 +1,3 @@
 pub mod custom_hashmap;
-pub mod custom_lru_cache;
\ No newline at end of file
+pub mod custom_lru_cache;
+pub mod concurrent_cache;
\ No newline at end of file
@@ -0,0 +1,97 @@
+// parsing of the free text fields of courses (credits, descriptions), shared by the exporters
+use std::ops::Range;
+
+// a course code mentioned within text, i.e. "CSc 10300" within "Prerequisite : CSc 10300"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CourseCodeMention {
+    pub range : Range<usize>,           // byte range of the mention within the text
+    pub course_code : String,           // normalized, i.e. "CSC 10300"
+}
+
+// course codes are a subject code followed by a course number, i.e. "CSC 10300" (or "CSc 10300", "CSC10300")
+// anything shaped like one is returned (i.e. "Fall 2024"), callers check them against the catalog
+pub(crate) fn find_course_codes(text : &str) -> Vec<CourseCodeMention> {
+    let mut mentions = Vec::new();
+    let bytes = text.as_bytes();
+    let mut position = 0;
+
+    while position < bytes.len() {
+        let at_word_start = position == 0 || !bytes[position - 1].is_ascii_alphanumeric();
+        if !at_word_start || !bytes[position].is_ascii_alphabetic() {
+            position += 1;
+            continue;
+        }
+
+        let subject_end = position + bytes[position..].iter().take_while(|byte| byte.is_ascii_alphabetic()).count();
+        let number_start = if bytes.get(subject_end) == Some(&b' ') { subject_end + 1 } else { subject_end };
+        let number_end = number_start + bytes[number_start..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+        let at_word_end = bytes.get(number_end).is_none_or(|byte| !byte.is_ascii_alphanumeric());
+
+        if number_end > number_start && at_word_end {
+            mentions.push(CourseCodeMention {
+                range : position..number_end,
+                course_code : normalize_course_code(&text[position..number_end]),
+            });
+            position = number_end;
+        } else {
+            position = subject_end;
+        }
+    }
+
+    mentions
+}
+
+// i.e. "CSc10300" becomes "CSC 10300"
+pub(crate) fn normalize_course_code(course_code : &str) -> String {
+    let subject : String = course_code.chars().take_while(|character| character.is_ascii_alphabetic()).collect();
+    let number = course_code[subject.len()..].trim();
+    format!("{} {number}", subject.to_ascii_uppercase())
+}
+
+// i.e. "3" is (3.0, 3.0) and "1 - 3" is (1.0, 3.0), anything else isn't parsed
+pub(crate) fn parse_credit_range(credits : &str) -> Option<(f64, f64)> {
+    let (min_credits, max_credits) = credits.split_once('-').unwrap_or((credits, credits));
+    Some((min_credits.trim().parse().ok()?, max_credits.trim().parse().ok()?))
+}
+
+// prerequisites are only found within the description, i.e. "... data structures. Prerequisite : CSC 10300. 4 hr./wk."
+// returns the sentence listing them, without the "Prerequisite :" label
+pub(crate) fn prerequisite_text(description : &str) -> Option<&str> {
+    let start = description.to_ascii_lowercase().find("prereq")?;
+    let sentence = &description[start..];
+
+    // the label ends at the first ':' (i.e. "Prerequisites:", "Pre-requisite(s) :"), if there is one close by
+    let sentence = match sentence.find(':') {
+        Some(colon) if colon < 24 => &sentence[colon + 1..],
+        _ => sentence.trim_start_matches(|character : char| character.is_alphabetic() || character == '(' || character == ')'),
+    };
+
+    // the sentence ends at a '.' followed by whitespace, course numbers never contain one
+    let end = sentence.char_indices()
+        .find(|(index, character)| *character == '.' && sentence[index + 1..].starts_with(char::is_whitespace))
+        .map_or(sentence.len(), |(index, _)| index);
+
+    let text = sentence[..end].trim().trim_end_matches('.').trim();
+    (!text.is_empty()).then_some(text)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_course_codes_and_prerequisites() {
+        let description = "Advanced data structures. Prerequisites: CSc 21200 and MATH20100 (Fall 2024 only). 4 hr./wk.";
+        let prerequisites = prerequisite_text(description).unwrap();
+        assert_eq!(prerequisites, "CSc 21200 and MATH20100 (Fall 2024 only)");
+
+        let course_codes : Vec<String> = find_course_codes(prerequisites).into_iter().map(|mention| mention.course_code).collect();
+        assert_eq!(course_codes, vec!["CSC 21200", "MATH 20100", "FALL 2024"]);
+        assert_eq!(prerequisite_text("Introduction to programming."), None);
+
+        assert_eq!(parse_credit_range("3"), Some((3.0, 3.0)));
+        assert_eq!(parse_credit_range("1 - 3"), Some((1.0, 3.0)));
+        assert_eq!(parse_credit_range("varies"), None);
+    }
+}
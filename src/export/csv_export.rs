@@ -0,0 +1,342 @@
+// csv export of course listings and course details, for spreadsheets
+//
+// nested fields are flattened so that every record is a single row with the same columns
+// - the component of a listing is split into one column per attribute (i.e. component_type, weekly_hours)
+// - lists (departments, the components of a course detail) are joined with ';' within a single column
+//   i.e. a course detail with a lecture and a lab has "LEC;LAB" as it's component_types
+//
+// the columns (and their order) are part of the format, new columns are only ever appended
+use anyhow::{Context, Result};
+use crate::models::{CourseComponents, CourseDetail, CourseInfo};
+use std::io::Write;
+
+// separates the values of list fields within a single column
+const LIST_SEPARATOR : &str = ";";
+
+/// Records that can be exported by [`CsvExporter`].
+pub trait CsvRecord {
+    type Column : CsvColumn;
+
+    fn value(&self, column : Self::Column) -> String;
+}
+
+/// Columns of a [`CsvRecord`].
+pub trait CsvColumn : Copy + Sized + 'static {
+    /// every column, in the order used when no columns are selected
+    fn all() -> Vec<Self>;
+
+    /// name of the column within the header row
+    fn header(self) -> &'static str;
+
+    /// finds the column whose header matches the name (ignoring case)
+    fn from_header(name : &str) -> Result<Self> {
+        Self::all()
+            .into_iter()
+            .find(|column| column.header().eq_ignore_ascii_case(name))
+            .with_context(|| format!(
+                "unknown column \"{name}\", expected one of : {}",
+                Self::all().into_iter().map(Self::header).collect::<Vec<&str>>().join(", ")
+            ))
+    }
+}
+
+/// Columns of a [`CourseInfo`] export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CourseColumn {
+    CourseGroupId,
+    CourseCode,
+    CourseName,
+    SubjectCode,
+    CourseNumber,
+    Credits,
+    Career,
+    Departments,
+    ComponentType,
+    AttendanceType,
+    WeeklyHours,
+    ClassSize,
+    FinalExam,
+    ExamSeatSpacing,
+    InstructionMode,
+    EffectiveStartDate,
+    EffectiveEndDate,
+    UniqueId,
+}
+
+/// Columns of a [`CourseDetail`] export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CourseDetailColumn {
+    CourseGroupId,
+    CourseCode,
+    CourseName,
+    LongName,
+    SubjectCode,
+    CourseNumber,
+    Credits,
+    Career,
+    College,
+    Departments,
+    ComponentTypes,
+    WeeklyHours,
+    ClassSizes,
+    InstructionModes,
+    TypicallyOffered,
+    Consent,
+    RequirementGroup,
+    Status,
+    Description,
+    UniqueId,
+}
+
+/// Writes records as csv, with a header row followed by one row per record.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::{CCNYCourseCatalog, CourseColumn, CsvExporter};
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let course_instance = CCNYCourseCatalog::new(String::from("computer science"), None);
+///     let list_of_courses = course_instance.get_courses_list().await?;
+///
+///     // every column
+///     CsvExporter::new().write(&list_of_courses, std::io::stdout())?;
+///
+///     // selected columns only
+///     let exporter = CsvExporter::with_columns(vec![CourseColumn::CourseCode, CourseColumn::CourseName, CourseColumn::Credits]);
+///     exporter.write(&list_of_courses, std::fs::File::create("courses.csv")?)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvExporter<C> {
+    columns : Vec<C>,
+}
+
+impl<C : CsvColumn> CsvExporter<C> {
+
+    /// Exports every column of the record.
+    pub fn new() -> Self {
+        CsvExporter { columns : C::all() }
+    }
+
+    /// Exports the provided columns, in the provided order.
+    pub fn with_columns(columns : Vec<C>) -> Self {
+        CsvExporter { columns }
+    }
+
+    /// Same as [`CsvExporter::with_columns`], with the columns given by their header names
+    /// (i.e. `"course_code,course_name,credits"`), useful for configuration files and command line arguments.
+    pub fn with_column_names(column_names : &str) -> Result<Self> {
+        let columns = column_names.split(',')
+            .map(str::trim)
+            .filter(|column_name| !column_name.is_empty())
+            .map(C::from_header)
+            .collect::<Result<Vec<C>>>()?;
+
+        Ok(CsvExporter::with_columns(columns))
+    }
+
+    pub fn columns(&self) -> &[C] {
+        &self.columns
+    }
+
+    pub fn write<R, W>(&self, records : &[R], writer : W) -> Result<()>
+    where
+        R : CsvRecord<Column = C>,
+        W : Write,
+    {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record(self.columns.iter().map(|column| column.header()))?;
+        for record in records {
+            csv_writer.write_record(self.columns.iter().map(|column| record.value(*column)))?;
+        }
+
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    pub fn to_csv_string<R : CsvRecord<Column = C>>(&self, records : &[R]) -> Result<String> {
+        let mut buffer = Vec::new();
+        self.write(records, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+impl<C : CsvColumn> Default for CsvExporter<C> {
+    fn default() -> Self {
+        CsvExporter::new()
+    }
+}
+
+impl CsvColumn for CourseColumn {
+    fn all() -> Vec<CourseColumn> {
+        use CourseColumn::*;
+        vec![
+            CourseGroupId, CourseCode, CourseName, SubjectCode, CourseNumber, Credits, Career, Departments,
+            ComponentType, AttendanceType, WeeklyHours, ClassSize, FinalExam, ExamSeatSpacing, InstructionMode,
+            EffectiveStartDate, EffectiveEndDate, UniqueId,
+        ]
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            CourseColumn::CourseGroupId => "course_group_id",
+            CourseColumn::CourseCode => "course_code",
+            CourseColumn::CourseName => "course_name",
+            CourseColumn::SubjectCode => "subject_code",
+            CourseColumn::CourseNumber => "course_number",
+            CourseColumn::Credits => "credits",
+            CourseColumn::Career => "career",
+            CourseColumn::Departments => "departments",
+            CourseColumn::ComponentType => "component_type",
+            CourseColumn::AttendanceType => "attendance_type",
+            CourseColumn::WeeklyHours => "weekly_hours",
+            CourseColumn::ClassSize => "class_size",
+            CourseColumn::FinalExam => "final_exam",
+            CourseColumn::ExamSeatSpacing => "exam_seat_spacing",
+            CourseColumn::InstructionMode => "instruction_mode",
+            CourseColumn::EffectiveStartDate => "effective_start_date",
+            CourseColumn::EffectiveEndDate => "effective_end_date",
+            CourseColumn::UniqueId => "unique_id",
+        }
+    }
+}
+
+impl CsvRecord for CourseInfo {
+    type Column = CourseColumn;
+
+    fn value(&self, column : CourseColumn) -> String {
+        let component = &self.course_components;
+        match column {
+            CourseColumn::CourseGroupId => self.course_group_id.clone(),
+            CourseColumn::CourseCode => self.course_code.clone(),
+            CourseColumn::CourseName => self.course_name.clone(),
+            CourseColumn::SubjectCode => self.subject_code.clone(),
+            CourseColumn::CourseNumber => self.course_number.to_string(),
+            CourseColumn::Credits => self.credits.clone(),
+            CourseColumn::Career => self.career.clone(),
+            CourseColumn::Departments => self.department.join(LIST_SEPARATOR),
+            CourseColumn::ComponentType => component.course_type.clone(),
+            CourseColumn::AttendanceType => component.attendance_type.clone(),
+            CourseColumn::WeeklyHours => component.weekly_hours.to_string(),
+            CourseColumn::ClassSize => component.class_size.to_string(),
+            CourseColumn::FinalExam => component.final_exam.clone(),
+            CourseColumn::ExamSeatSpacing => component.exam_seat_spacing.to_string(),
+            CourseColumn::InstructionMode => component.instruction_mode.clone(),
+            CourseColumn::EffectiveStartDate => self.effective_start_date.clone(),
+            CourseColumn::EffectiveEndDate => self.effective_end_date.clone(),
+            CourseColumn::UniqueId => self.unique_id.clone(),
+        }
+    }
+}
+
+impl CsvColumn for CourseDetailColumn {
+    fn all() -> Vec<CourseDetailColumn> {
+        use CourseDetailColumn::*;
+        vec![
+            CourseGroupId, CourseCode, CourseName, LongName, SubjectCode, CourseNumber, Credits, Career, College,
+            Departments, ComponentTypes, WeeklyHours, ClassSizes, InstructionModes, TypicallyOffered, Consent,
+            RequirementGroup, Status, Description, UniqueId,
+        ]
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            CourseDetailColumn::CourseGroupId => "course_group_id",
+            CourseDetailColumn::CourseCode => "course_code",
+            CourseDetailColumn::CourseName => "course_name",
+            CourseDetailColumn::LongName => "long_name",
+            CourseDetailColumn::SubjectCode => "subject_code",
+            CourseDetailColumn::CourseNumber => "course_number",
+            CourseDetailColumn::Credits => "credits",
+            CourseDetailColumn::Career => "career",
+            CourseDetailColumn::College => "college",
+            CourseDetailColumn::Departments => "departments",
+            CourseDetailColumn::ComponentTypes => "component_types",
+            CourseDetailColumn::WeeklyHours => "weekly_hours",
+            CourseDetailColumn::ClassSizes => "class_sizes",
+            CourseDetailColumn::InstructionModes => "instruction_modes",
+            CourseDetailColumn::TypicallyOffered => "typically_offered",
+            CourseDetailColumn::Consent => "consent",
+            CourseDetailColumn::RequirementGroup => "requirement_group",
+            CourseDetailColumn::Status => "status",
+            CourseDetailColumn::Description => "description",
+            CourseDetailColumn::UniqueId => "unique_id",
+        }
+    }
+}
+
+impl CsvRecord for CourseDetail {
+    type Column = CourseDetailColumn;
+
+    fn value(&self, column : CourseDetailColumn) -> String {
+        match column {
+            CourseDetailColumn::CourseGroupId => self.course_group_id.clone(),
+            CourseDetailColumn::CourseCode => self.course_code.clone(),
+            CourseDetailColumn::CourseName => self.course_name.clone(),
+            CourseDetailColumn::LongName => self.long_name.clone(),
+            CourseDetailColumn::SubjectCode => self.subject_code.clone(),
+            CourseDetailColumn::CourseNumber => self.course_number.clone(),
+            CourseDetailColumn::Credits => self.credits.clone(),
+            CourseDetailColumn::Career => self.career.clone(),
+            CourseDetailColumn::College => self.college.clone(),
+            CourseDetailColumn::Departments => self.departments.join(LIST_SEPARATOR),
+            CourseDetailColumn::ComponentTypes => join_components(&self.course_components, |component| component.course_type.clone()),
+            CourseDetailColumn::WeeklyHours => join_components(&self.course_components, |component| component.weekly_hours.to_string()),
+            CourseDetailColumn::ClassSizes => join_components(&self.course_components, |component| component.class_size.to_string()),
+            CourseDetailColumn::InstructionModes => join_components(&self.course_components, |component| component.instruction_mode.clone()),
+            CourseDetailColumn::TypicallyOffered => self.typically_offered.clone(),
+            CourseDetailColumn::Consent => self.consent.clone(),
+            CourseDetailColumn::RequirementGroup => self.requirement_group.clone(),
+            CourseDetailColumn::Status => self.status.clone(),
+            CourseDetailColumn::Description => self.description.clone(),
+            CourseDetailColumn::UniqueId => self.unique_id.clone(),
+        }
+    }
+}
+
+fn join_components(components : &[CourseComponents], attribute : impl Fn(&CourseComponents) -> String) -> String {
+    components.iter().map(attribute).collect::<Vec<String>>().join(LIST_SEPARATOR)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::catalog_snapshot::tests::sample_snapshot;
+
+    #[test]
+    fn test_course_listing_export() {
+        let courses = sample_snapshot().departments["CSC-CTY"].clone();
+        let exported = CsvExporter::new().to_csv_string(&courses).unwrap();
+        let mut lines = exported.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "course_group_id,course_code,course_name,subject_code,course_number,credits,career,departments,\
+            component_type,attendance_type,weekly_hours,class_size,final_exam,exam_seat_spacing,instruction_mode,\
+            effective_start_date,effective_end_date,unique_id"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "0455351,CSC 21200,Data Structures,CSC,21200,3,Undergraduate,CSC-CTY,\
+            LEC,Class Meeting,4,35,Yes,1,In Person,1901-01-01,unknown,0455351-1901-01-01"
+        );
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_selected_columns_and_nested_fields() {
+        let mut detail = sample_snapshot().course_details["0455351"].clone();
+        detail.description = String::from("Recursion, \"complexity\" analysis");
+        let lab = CourseComponents { course_type : String::from("LAB"), weekly_hours : 2, ..detail.course_components[0].clone() };
+        detail.course_components.push(lab);
+
+        let exporter = CsvExporter::<CourseDetailColumn>::with_column_names("course_code, component_types,weekly_hours,description").unwrap();
+        let exported = exporter.to_csv_string(&[detail]).unwrap();
+        assert_eq!(exported, "course_code,component_types,weekly_hours,description\nCSC 21200,LEC;LAB,4;2,\"Recursion, \"\"complexity\"\" analysis\"\n");
+
+        let error = CsvExporter::<CourseColumn>::with_column_names("course_code,room").unwrap_err();
+        assert!(error.to_string().contains("unknown column \"room\""));
+    }
+}
@@ -0,0 +1,220 @@
+// schema.org Course markup (json-ld), so that search engines can index pages about courses
+// https://schema.org/Course
+//
+// prerequisites aren't a separate field of the catalog, they are taken from the description
+// i.e. "Prerequisite : CSC 10300" becomes [{ "@type" : "Course", "courseCode" : "CSC 10300" }]
+use anyhow::Result;
+use crate::api::sans_io;
+use crate::export::course_text::{find_course_codes, parse_credit_range, prerequisite_text};
+use crate::models::{CourseDetail, CourseInfo};
+use crate::snapshot::CatalogSnapshot;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const SCHEMA_ORG_CONTEXT : &str = "https://schema.org";
+
+/// schema.org `Course` markup of a single course.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::{CCNYCourseCatalog, CourseJsonLd};
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let course_instance = CCNYCourseCatalog::new(String::from("computer science"), Some(String::from("data structures")));
+///     let course_detail = course_instance.get_course_detail().await?;
+///
+///     // embedded within the <head> of a page about the course
+///     println!("{}", CourseJsonLd::from_detail(&course_detail).to_html_script()?);
+///     Ok(())
+/// }
+/// ```
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseJsonLd {
+    #[serde(rename = "@context")]
+    pub context : String,
+    #[serde(rename = "@type")]
+    pub kind : String,                                          // always "Course"
+    pub name : String,
+    pub course_code : String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description : String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_credits : Option<Value>,                      // a number, or a StructuredValue for ranges (i.e. "1 - 3")
+    pub provider : JsonLdOrganization,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub course_prerequisites : Vec<CoursePrerequisite>
+}
+
+/// The organization offering a course, City College for every course of the catalog.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct JsonLdOrganization {
+    #[serde(rename = "@type")]
+    pub kind : String,
+    pub name : String,
+    pub url : String
+}
+
+impl Default for JsonLdOrganization {
+    fn default() -> Self {
+        JsonLdOrganization {
+            kind : String::from("CollegeOrUniversity"),
+            name : String::from("The City College of New York"),
+            url : String::from("https://www.ccny.cuny.edu"),
+        }
+    }
+}
+
+/// A prerequisite of a course, either another course or text when no course codes are mentioned.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum CoursePrerequisite {
+    Course {
+        #[serde(rename = "@type")]
+        kind : String,
+        #[serde(rename = "courseCode")]
+        course_code : String,
+    },
+    Text(String),
+}
+
+impl CourseJsonLd {
+
+    /// Markup of a listed course, the description and prerequisites are only known when the details of the course are provided.
+    pub fn new(course : &CourseInfo, detail : Option<&CourseDetail>) -> CourseJsonLd {
+        match detail {
+            Some(detail) => CourseJsonLd::from_detail(detail),
+            None => CourseJsonLd::with_fields(&course.course_name, &course.course_code, "", &course.credits),
+        }
+    }
+
+    /// Markup of a course, from it's details (i.e. the result of [`crate::CCNYCourseCatalog::get_course_detail`]).
+    pub fn from_detail(detail : &CourseDetail) -> CourseJsonLd {
+        CourseJsonLd::with_fields(&detail.course_name, &detail.course_code, &detail.description, &detail.credits)
+    }
+
+    /// Markup of every course within the snapshot, ordered by course group id.
+    pub fn for_snapshot(snapshot : &CatalogSnapshot) -> Vec<CourseJsonLd> {
+        let mut courses = BTreeMap::new();
+        for course in snapshot.courses() {
+            courses.entry(sans_io::normalize_course_group_id(&course.course_group_id)).or_insert(course);
+        }
+
+        courses.into_iter()
+            .map(|(course_group_id, course)| CourseJsonLd::new(course, snapshot.course_details.get(&course_group_id)))
+            .collect()
+    }
+
+    fn with_fields(name : &str, course_code : &str, description : &str, credits : &str) -> CourseJsonLd {
+        CourseJsonLd {
+            context : SCHEMA_ORG_CONTEXT.to_owned(),
+            kind : String::from("Course"),
+            name : name.to_owned(),
+            course_code : course_code.to_owned(),
+            description : description.to_owned(),
+            number_of_credits : number_of_credits(credits),
+            provider : JsonLdOrganization::default(),
+            course_prerequisites : prerequisites(description),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The markup wrapped within a `<script type="application/ld+json">` element.
+    pub fn to_html_script(&self) -> Result<String> {
+        // "</" would end the script element early, i.e. within a description mentioning "</script>"
+        let json = serde_json::to_string(self)?.replace("</", "<\\/");
+        Ok(format!("<script type=\"application/ld+json\">{json}</script>"))
+    }
+}
+
+fn number_of_credits(credits : &str) -> Option<Value> {
+    let (min_credits, max_credits) = parse_credit_range(credits)?;
+    if min_credits == max_credits {
+        return Some(credit_value(min_credits));
+    }
+
+    Some(serde_json::json!({
+        "@type" : "StructuredValue",
+        "minValue" : credit_value(min_credits),
+        "maxValue" : credit_value(max_credits),
+    }))
+}
+
+// whole credits are written as integers, i.e. 3 rather than 3.0
+fn credit_value(credits : f64) -> Value {
+    if credits.fract() == 0.0 {
+        Value::from(credits as i64)
+    } else {
+        Value::from(credits)
+    }
+}
+
+fn prerequisites(description : &str) -> Vec<CoursePrerequisite> {
+    let Some(text) = prerequisite_text(description) else {
+        return Vec::new();
+    };
+
+    // course numbers of the catalog are 5 digits long, which rules out mentions such as "Fall 2024"
+    let mut course_codes : Vec<String> = find_course_codes(text)
+        .into_iter()
+        .map(|mention| mention.course_code)
+        .filter(|course_code| course_code.rsplit(' ').next().is_some_and(|number| number.len() == 5))
+        .collect();
+    course_codes.dedup();
+
+    if course_codes.is_empty() {
+        return vec![CoursePrerequisite::Text(text.to_owned())];
+    }
+
+    course_codes.into_iter()
+        .map(|course_code| CoursePrerequisite::Course { kind : String::from("Course"), course_code })
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::catalog_snapshot::tests::sample_snapshot;
+
+    #[test]
+    fn test_course_markup() {
+        let mut detail = sample_snapshot().course_details["0455351"].clone();
+        detail.description = String::from("Trees and graphs. Prerequisites: CSc 10300 and MATH 20100. 4 hr./wk.");
+        let markup : Value = serde_json::from_str(&CourseJsonLd::from_detail(&detail).to_json().unwrap()).unwrap();
+
+        assert_eq!(markup, serde_json::json!({
+            "@context" : "https://schema.org",
+            "@type" : "Course",
+            "name" : "Data Structures",
+            "courseCode" : "CSC 21200",
+            "description" : "Trees and graphs. Prerequisites: CSc 10300 and MATH 20100. 4 hr./wk.",
+            "numberOfCredits" : 3,
+            "provider" : { "@type" : "CollegeOrUniversity", "name" : "The City College of New York", "url" : "https://www.ccny.cuny.edu" },
+            "coursePrerequisites" : [
+                { "@type" : "Course", "courseCode" : "CSC 10300" },
+                { "@type" : "Course", "courseCode" : "MATH 20100" }
+            ]
+        }));
+    }
+
+    #[test]
+    fn test_listing_markup() {
+        let snapshot = sample_snapshot();
+        let mut course = snapshot.departments["CSC-CTY"][0].clone();
+        course.credits = String::from("1 - 3");
+        course.course_name = String::from("</script> Topics");
+
+        let markup = CourseJsonLd::new(&course, None);
+        assert!(markup.description.is_empty() && markup.course_prerequisites.is_empty());
+        assert_eq!(markup.number_of_credits, Some(serde_json::json!({ "@type" : "StructuredValue", "minValue" : 1, "maxValue" : 3 })));
+        assert!(!markup.to_html_script().unwrap().contains("</script> Topics"));
+
+        assert_eq!(CourseJsonLd::for_snapshot(&snapshot)[0].description, snapshot.course_details["0455351"].description);
+    }
+}
@@ -0,0 +1,14 @@
+// exporters turning course listings and snapshots into formats used outside of rust
+
+mod course_text;
+pub mod csv_export;
+pub mod json_ld;
+pub mod ndjson_export;
+pub mod site_generator;
+pub mod sqlite_export;
+
+pub use self::csv_export::{CourseColumn, CourseDetailColumn, CsvColumn, CsvExporter, CsvRecord};
+pub use self::json_ld::{CourseJsonLd, CoursePrerequisite, JsonLdOrganization};
+pub use self::ndjson_export::{NdjsonCompression, NdjsonWriter};
+pub use self::site_generator::{SiteFormat, SiteGenerator, SitePage};
+pub use self::sqlite_export::{SqliteExporter, SQLITE_SCHEMA_VERSION};
@@ -0,0 +1,229 @@
+// newline delimited json (one json value per line), for piping into jq or loading into data tools
+//
+// unlike a snapshot, records are written as they are downloaded instead of being collected in memory first
+// i.e. `zcat courses.ndjson.gz | jq -r 'select(.subject_code == "CSC") | .course_name'`
+use anyhow::{Context, Result};
+use async_compression::tokio::write::GzipEncoder;
+use crate::api::department::coursedog_base_url;
+use crate::api::sans_io;
+use crate::schema::MODEL_SCHEMA_VERSION;
+use crate::snapshot::catalog_snapshot::{course_details, department_listings};
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+
+/// Compression applied to the output of a [`NdjsonWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NdjsonCompression {
+    #[default]
+    None,
+    Gzip,
+}
+
+// records are json objects, the version is added as another field
+#[derive(Serialize)]
+struct VersionedRecord<'a, T> {
+    schema_version : u32,
+    #[serde(flatten)]
+    record : &'a T,
+}
+
+enum Encoder<W : AsyncWrite + Unpin> {
+    Plain(BufWriter<W>),
+    Gzip(GzipEncoder<BufWriter<W>>),
+}
+
+/// Writes records as newline delimited json, one record per line.
+///
+/// [`NdjsonWriter::finish`] needs to be called once every record is written,
+/// otherwise buffered records (and the end of the gzip stream) may be lost.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::NdjsonWriter;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     // compressed, since the path ends with .gz
+///     let mut writer = NdjsonWriter::create("courses.ndjson.gz").await?;
+///     let course_count = writer.write_all_courses().await?;
+///     writer.finish().await?;
+///     println!("exported {course_count} courses");
+///     Ok(())
+/// }
+/// ```
+pub struct NdjsonWriter<W : AsyncWrite + Unpin> {
+    encoder : Encoder<W>,
+    records_written : usize,
+
+    // Coursedog API the courses are downloaded from, refer to set_base_url
+    base_url : String,
+}
+
+impl NdjsonWriter<tokio::fs::File> {
+    /// Creates (or truncates) the file at `path`, the output is gzip compressed if the path ends with `.gz`.
+    pub async fn create(path : impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let compression = match path.extension() {
+            Some(extension) if extension == "gz" => NdjsonCompression::Gzip,
+            _ => NdjsonCompression::None,
+        };
+
+        let file = tokio::fs::File::create(path).await.with_context(|| format!("failed to create {}", path.display()))?;
+        Ok(NdjsonWriter::new(file, compression))
+    }
+}
+
+impl<W : AsyncWrite + Unpin> NdjsonWriter<W> {
+
+    /// Writes into any async writer (i.e. `tokio::io::stdout()`).
+    pub fn new(writer : W, compression : NdjsonCompression) -> Self {
+        let writer = BufWriter::new(writer);
+        let encoder = match compression {
+            NdjsonCompression::None => Encoder::Plain(writer),
+            NdjsonCompression::Gzip => Encoder::Gzip(GzipEncoder::new(writer)),
+        };
+
+        NdjsonWriter { encoder, records_written : 0, base_url : coursedog_base_url() }
+    }
+
+    /// Downloads the courses from another Coursedog API (i.e. the `coursedog-mock` stand-in server),
+    /// same as [`crate::CCNYCourseCatalog::set_base_url`].
+    pub fn set_base_url(&mut self, base_url : &str) {
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+    }
+
+    /// Writes the record as a single line, along with a `schema_version` field holding [`crate::MODEL_SCHEMA_VERSION`].
+    pub async fn write_record<T : Serialize>(&mut self, record : &T) -> Result<()> {
+        // serde_json never emits newlines within compact output, so every record stays on a single line
+        let mut line = serde_json::to_vec(&VersionedRecord { schema_version : MODEL_SCHEMA_VERSION, record })?;
+        line.push(b'\n');
+
+        match &mut self.encoder {
+            Encoder::Plain(writer) => writer.write_all(&line).await?,
+            Encoder::Gzip(writer) => writer.write_all(&line).await?,
+        }
+
+        self.records_written += 1;
+        Ok(())
+    }
+
+    pub async fn write_records<'a, T : Serialize + 'a>(&mut self, records : impl IntoIterator<Item = &'a T>) -> Result<()> {
+        for record in records {
+            self.write_record(record).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the listing of every department, writing each course ([`crate::CourseInfo`]) as soon as it's department is downloaded.
+    /// Courses cross listed under several departments are only written once.
+    ///
+    /// Returns the number of courses written.
+    pub async fn write_all_courses(&mut self) -> Result<usize> {
+        let mut written_course_group_ids = HashSet::new();
+        let mut listings = std::pin::pin!(department_listings(&self.base_url));
+        while let Some((_, courses)) = listings.next().await {
+            for course in courses? {
+                if written_course_group_ids.insert(sans_io::normalize_course_group_id(&course.course_group_id)) {
+                    self.write_record(&course).await?;
+                }
+            }
+        }
+
+        Ok(written_course_group_ids.len())
+    }
+
+    /// Downloads the details ([`crate::CourseDetail`]) of every course, writing each as soon as it's downloaded.
+    /// The department listings are downloaded first, to find every course.
+    ///
+    /// Returns the number of course details written.
+    pub async fn write_all_course_details(&mut self) -> Result<usize> {
+        let mut course_group_ids = Vec::new();
+        let mut seen_course_group_ids = HashSet::new();
+        let mut listings = std::pin::pin!(department_listings(&self.base_url));
+        while let Some((_, courses)) = listings.next().await {
+            for course in courses? {
+                let course_group_id = sans_io::normalize_course_group_id(&course.course_group_id);
+                if seen_course_group_ids.insert(course_group_id.clone()) {
+                    course_group_ids.push(course_group_id);
+                }
+            }
+        }
+
+        let mut written = 0;
+        let mut details = std::pin::pin!(course_details(&self.base_url, course_group_ids));
+        while let Some((_, detail)) = details.next().await {
+            if let Some(detail) = detail? {
+                self.write_record(&detail).await?;
+                written += 1;
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// Number of records written so far.
+    pub fn records_written(&self) -> usize {
+        self.records_written
+    }
+
+    /// Flushes the remaining records (ending the gzip stream) and returns the underlying writer.
+    pub async fn finish(self) -> Result<W> {
+        let writer = match self.encoder {
+            Encoder::Plain(mut writer) => {
+                writer.flush().await?;
+                writer
+            },
+            Encoder::Gzip(mut writer) => {
+                writer.shutdown().await?;
+                writer.into_inner()
+            },
+        };
+
+        Ok(writer.into_inner())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CourseInfo;
+    use crate::snapshot::catalog_snapshot::tests::sample_snapshot;
+    use async_compression::tokio::bufread::GzipDecoder;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_records_are_written_one_per_line() {
+        let courses = sample_snapshot().departments["CSC-CTY"].clone();
+        let mut writer = NdjsonWriter::new(Vec::new(), NdjsonCompression::None);
+        writer.write_records(&courses).await.unwrap();
+        writer.write_records(&courses).await.unwrap();
+        assert_eq!(writer.records_written(), 2);
+
+        let output = String::from_utf8(writer.finish().await.unwrap()).unwrap();
+        let lines : Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let course : CourseInfo = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(course, courses[0]);
+        assert!(lines[0].starts_with("{\"schema_version\":1,"));
+    }
+
+    #[tokio::test]
+    async fn test_gzip_compression() {
+        let snapshot = sample_snapshot();
+        let mut writer = NdjsonWriter::new(Vec::new(), NdjsonCompression::Gzip);
+        writer.write_records(snapshot.course_details.values()).await.unwrap();
+        let compressed = writer.finish().await.unwrap();
+        assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+
+        let mut output = String::new();
+        GzipDecoder::new(compressed.as_slice()).read_to_string(&mut output).await.unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.ends_with("}\n"));
+        assert!(output.contains("\"course_code\":\"CSC 21200\""));
+    }
+}
@@ -0,0 +1,575 @@
+// static copy of the catalog, rendered as html or markdown pages
+//
+// layout of the generated site
+// - index.{html,md} : every department, along with a search box (html only)
+// - departments/{department id}.{html,md} : every course of the department
+// - courses/{course group id}.{html,md} : details of a single course
+// - search-index.js : course codes, names and descriptions searched by the index page (html only)
+//
+// course codes mentioned within descriptions (i.e. "Prerequisite : CSC 10300") link to the page of that course,
+// and every course page lists the courses referencing it
+use anyhow::{Context, Result};
+use crate::api::department::get_department_mappings;
+use crate::api::sans_io;
+use crate::export::course_text::{find_course_codes, normalize_course_code};
+use crate::export::json_ld::CourseJsonLd;
+use crate::models::{CourseDetail, CourseInfo};
+use crate::snapshot::CatalogSnapshot;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Format of the pages rendered by [`SiteGenerator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiteFormat {
+    Html,
+    Markdown,
+}
+
+impl SiteFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            SiteFormat::Html => "html",
+            SiteFormat::Markdown => "md",
+        }
+    }
+}
+
+/// A rendered page, with it's path relative to the root of the site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitePage {
+    pub path : PathBuf,
+    pub contents : String
+}
+
+/// Renders a [`CatalogSnapshot`] into a static site, with a page per department and per course.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::{CatalogSnapshot, SiteFormat, SiteGenerator};
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let snapshot = CatalogSnapshot::fetch(true).await?;
+///     let generator = SiteGenerator::new(&snapshot).with_title("CCNY Course Catalog");
+///     generator.write("site", SiteFormat::Html)?;
+///     generator.write("docs", SiteFormat::Markdown)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SiteGenerator<'a> {
+    title : String,
+    courses : Vec<CourseEntry<'a>>,                             // ordered by course code
+    department_names : BTreeMap<String, String>,                // department id to it's name
+    course_group_ids_by_code : HashMap<String, String>,         // i.e. "CSC 21200" to "0455351"
+    referenced_by : HashMap<String, BTreeSet<usize>>,           // course group id to the courses mentioning it
+}
+
+// everything known about a single course
+#[derive(Debug, Clone)]
+struct CourseEntry<'a> {
+    course_group_id : String,
+    listing : &'a CourseInfo,
+    detail : Option<&'a CourseDetail>,
+}
+
+impl CourseEntry<'_> {
+    fn description(&self) -> &str {
+        self.detail.map_or("", |detail| detail.description.as_str())
+    }
+}
+
+// part of a description, course codes are turned into links
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Course { text : &'a str, course_group_id : &'a str },
+}
+
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    course_code : &'a str,
+    course_name : &'a str,
+    url : String,
+    text : String,          // lowercased course code, name and description, matched against the query
+}
+
+impl<'a> SiteGenerator<'a> {
+
+    pub fn new(snapshot : &'a CatalogSnapshot) -> SiteGenerator<'a> {
+        // cross listed courses appear under every department, the first listing is used
+        let mut listings : BTreeMap<String, &CourseInfo> = BTreeMap::new();
+        for course in snapshot.courses() {
+            listings.entry(sans_io::normalize_course_group_id(&course.course_group_id)).or_insert(course);
+        }
+
+        let mut courses : Vec<CourseEntry> = listings.into_iter()
+            .map(|(course_group_id, listing)| CourseEntry {
+                detail : snapshot.course_details.get(&course_group_id),
+                course_group_id,
+                listing,
+            })
+            .collect();
+        courses.sort_by(|a, b| a.listing.course_code.cmp(&b.listing.course_code).then(a.course_group_id.cmp(&b.course_group_id)));
+
+        let course_group_ids_by_code : HashMap<String, String> = courses.iter()
+            .map(|course| (normalize_course_code(&course.listing.course_code), course.course_group_id.clone()))
+            .collect();
+
+        let mut referenced_by : HashMap<String, BTreeSet<usize>> = HashMap::new();
+        for (index, course) in courses.iter().enumerate() {
+            for segment in link_course_codes(course.description(), &course_group_ids_by_code) {
+                if let Segment::Course { course_group_id, .. } = segment {
+                    if course_group_id != course.course_group_id {
+                        referenced_by.entry(course_group_id.to_owned()).or_default().insert(index);
+                    }
+                }
+            }
+        }
+
+        let mut department_names : BTreeMap<String, String> = get_department_mappings()
+            .into_iter()
+            .map(|(department_name, department_code)| (department_code, title_case(&department_name)))
+            .collect();
+        department_names.retain(|department_code, _| snapshot.departments.contains_key(department_code));
+
+        SiteGenerator {
+            title : String::from("CCNY Course Catalog"),
+            courses,
+            department_names,
+            course_group_ids_by_code,
+            referenced_by,
+        }
+    }
+
+    /// Title of the index page, defaults to "CCNY Course Catalog".
+    pub fn with_title(mut self, title : impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Renders every page of the site, without writing them anywhere.
+    pub fn render(&self, format : SiteFormat) -> Result<Vec<SitePage>> {
+        let extension = format.extension();
+        let mut pages = vec![SitePage {
+            path : PathBuf::from(format!("index.{extension}")),
+            contents : self.render_index(format),
+        }];
+
+        for department_code in self.department_names.keys() {
+            pages.push(SitePage {
+                path : PathBuf::from(format!("departments/{department_code}.{extension}")),
+                contents : self.render_department(department_code, format),
+            });
+        }
+
+        for course in &self.courses {
+            pages.push(SitePage {
+                path : PathBuf::from(format!("courses/{}.{extension}", course.course_group_id)),
+                contents : self.render_course(course, format)?,
+            });
+        }
+
+        if format == SiteFormat::Html {
+            pages.push(SitePage {
+                path : PathBuf::from("search-index.js"),
+                contents : self.render_search_index()?,
+            });
+        }
+
+        Ok(pages)
+    }
+
+    /// Renders every page of the site into `directory`, returning the number of files written.
+    /// Existing pages are overwritten, pages of courses that no longer exist are left in place.
+    pub fn write(&self, directory : impl AsRef<Path>, format : SiteFormat) -> Result<usize> {
+        let directory = directory.as_ref();
+        let pages = self.render(format)?;
+        for page in &pages {
+            let path = directory.join(&page.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+
+            std::fs::write(&path, &page.contents).with_context(|| format!("failed to write {}", path.display()))?;
+        }
+
+        Ok(pages.len())
+    }
+
+    fn render_index(&self, format : SiteFormat) -> String {
+        let course_counts = self.department_course_counts();
+        match format {
+            SiteFormat::Html => {
+                let mut body = format!("<h1>{}</h1>\n", escape_html(&self.title));
+                body.push_str(SEARCH_BOX);
+                body.push_str("<h2>Departments</h2>\n<ul>\n");
+                for (department_code, department_name) in &self.department_names {
+                    body.push_str(&format!(
+                        "<li><a href=\"departments/{department_code}.html\">{}</a> ({} courses)</li>\n",
+                        escape_html(department_name), course_counts.get(department_code.as_str()).unwrap_or(&0)
+                    ));
+                }
+                body.push_str("</ul>\n");
+                html_layout(&self.title, "", &body)
+            },
+            SiteFormat::Markdown => {
+                let mut page = format!("# {}\n\n## Departments\n\n", escape_markdown(&self.title));
+                for (department_code, department_name) in &self.department_names {
+                    page.push_str(&format!(
+                        "- [{}](departments/{department_code}.md) ({} courses)\n",
+                        escape_markdown(department_name), course_counts.get(department_code.as_str()).unwrap_or(&0)
+                    ));
+                }
+                page
+            },
+        }
+    }
+
+    fn render_department(&self, department_code : &str, format : SiteFormat) -> String {
+        let department_name = &self.department_names[department_code];
+        let courses = self.courses.iter().filter(|course| course.listing.department.iter().any(|code| code == department_code));
+        let extension = format.extension();
+
+        match format {
+            SiteFormat::Html => {
+                let mut body = format!("<p><a href=\"../index.html\">{}</a></p>\n<h1>{}</h1>\n<ul>\n", escape_html(&self.title), escape_html(department_name));
+                for course in courses {
+                    body.push_str(&format!(
+                        "<li><a href=\"../courses/{}.{extension}\">{} {}</a></li>\n",
+                        course.course_group_id, escape_html(&course.listing.course_code), escape_html(&course.listing.course_name)
+                    ));
+                }
+                body.push_str("</ul>\n");
+                html_layout(department_name, "", &body)
+            },
+            SiteFormat::Markdown => {
+                let mut page = format!("[{}](../index.md)\n\n# {}\n\n", escape_markdown(&self.title), escape_markdown(department_name));
+                for course in courses {
+                    page.push_str(&format!(
+                        "- [{} {}](../courses/{}.{extension})\n",
+                        escape_markdown(&course.listing.course_code), escape_markdown(&course.listing.course_name), course.course_group_id
+                    ));
+                }
+                page
+            },
+        }
+    }
+
+    fn render_course(&self, course : &CourseEntry, format : SiteFormat) -> Result<String> {
+        let listing = course.listing;
+        let title = format!("{} {}", listing.course_code, listing.course_name);
+        let extension = format.extension();
+
+        // (label, value) pairs, rendered the same way within both formats
+        let mut fields = vec![
+            ("Credits", listing.credits.clone()),
+            ("Career", listing.career.clone()),
+        ];
+        if let Some(detail) = course.detail {
+            fields.push(("Typically offered", detail.typically_offered.clone()));
+            fields.push(("Consent", detail.consent.clone()));
+        }
+        fields.retain(|(_, value)| !value.is_empty());
+
+        let components = course.detail
+            .map_or_else(|| vec![&listing.course_components], |detail| detail.course_components.iter().collect());
+        let departments : Vec<(&str, &str)> = listing.department.iter()
+            .filter_map(|code| self.department_names.get(code).map(|name| (code.as_str(), name.as_str())))
+            .collect();
+        let referenced_by : Vec<&CourseEntry> = self.referenced_by.get(&course.course_group_id)
+            .into_iter()
+            .flatten()
+            .map(|index| &self.courses[*index])
+            .collect();
+        let description = link_course_codes(course.description(), &self.course_group_ids_by_code);
+
+        match format {
+            SiteFormat::Html => {
+                let mut body = format!("<p><a href=\"../index.html\">{}</a></p>\n<h1>{}</h1>\n", escape_html(&self.title), escape_html(&title));
+                if let Some(long_name) = course.detail.map(|detail| &detail.long_name).filter(|long_name| !long_name.is_empty()) {
+                    body.push_str(&format!("<p><em>{}</em></p>\n", escape_html(long_name)));
+                }
+
+                body.push_str("<dl>\n");
+                for (label, value) in &fields {
+                    body.push_str(&format!("<dt>{label}</dt><dd>{}</dd>\n", escape_html(value)));
+                }
+                body.push_str("<dt>Departments</dt><dd>");
+                body.push_str(&departments.iter()
+                    .map(|(code, name)| format!("<a href=\"../departments/{code}.html\">{}</a>", escape_html(name)))
+                    .collect::<Vec<String>>()
+                    .join(", "));
+                body.push_str("</dd>\n</dl>\n");
+
+                if !description.is_empty() {
+                    body.push_str("<h2>Description</h2>\n<p>");
+                    for segment in &description {
+                        match segment {
+                            Segment::Text(text) => body.push_str(&escape_html(text)),
+                            Segment::Course { text, course_group_id } => body.push_str(&format!("<a href=\"{course_group_id}.html\">{}</a>", escape_html(text))),
+                        }
+                    }
+                    body.push_str("</p>\n");
+                }
+
+                body.push_str("<h2>Components</h2>\n<table>\n<tr><th>Type</th><th>Weekly hours</th><th>Class size</th><th>Instruction mode</th></tr>\n");
+                for component in components {
+                    body.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        escape_html(&component.course_type), component.weekly_hours, component.class_size, escape_html(&component.instruction_mode)
+                    ));
+                }
+                body.push_str("</table>\n");
+
+                if !referenced_by.is_empty() {
+                    body.push_str("<h2>Referenced by</h2>\n<ul>\n");
+                    for referencing in referenced_by {
+                        body.push_str(&format!(
+                            "<li><a href=\"{}.{extension}\">{} {}</a></li>\n",
+                            referencing.course_group_id, escape_html(&referencing.listing.course_code), escape_html(&referencing.listing.course_name)
+                        ));
+                    }
+                    body.push_str("</ul>\n");
+                }
+
+                let markup = CourseJsonLd::new(listing, course.detail).to_html_script()? + "\n";
+                Ok(html_layout(&title, &markup, &body))
+            },
+            SiteFormat::Markdown => {
+                let mut page = format!("[{}](../index.md)\n\n# {}\n\n", escape_markdown(&self.title), escape_markdown(&title));
+                if let Some(long_name) = course.detail.map(|detail| &detail.long_name).filter(|long_name| !long_name.is_empty()) {
+                    page.push_str(&format!("*{}*\n\n", escape_markdown(long_name)));
+                }
+
+                for (label, value) in &fields {
+                    page.push_str(&format!("- **{label}** : {}\n", escape_markdown(value)));
+                }
+                page.push_str(&format!(
+                    "- **Departments** : {}\n",
+                    departments.iter()
+                        .map(|(code, name)| format!("[{}](../departments/{code}.md)", escape_markdown(name)))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ));
+
+                if !description.is_empty() {
+                    page.push_str("\n## Description\n\n");
+                    for segment in &description {
+                        match segment {
+                            Segment::Text(text) => page.push_str(&escape_markdown(text)),
+                            Segment::Course { text, course_group_id } => page.push_str(&format!("[{}]({course_group_id}.md)", escape_markdown(text))),
+                        }
+                    }
+                    page.push('\n');
+                }
+
+                page.push_str("\n## Components\n\n| Type | Weekly hours | Class size | Instruction mode |\n| --- | --- | --- | --- |\n");
+                for component in components {
+                    page.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        escape_markdown(&component.course_type), component.weekly_hours, component.class_size, escape_markdown(&component.instruction_mode)
+                    ));
+                }
+
+                if !referenced_by.is_empty() {
+                    page.push_str("\n## Referenced by\n\n");
+                    for referencing in referenced_by {
+                        page.push_str(&format!(
+                            "- [{} {}]({}.{extension})\n",
+                            escape_markdown(&referencing.listing.course_code), escape_markdown(&referencing.listing.course_name), referencing.course_group_id
+                        ));
+                    }
+                }
+
+                Ok(page)
+            },
+        }
+    }
+
+    // loaded through a script tag rather than fetched, so that the search also works when the pages are opened from disk
+    fn render_search_index(&self) -> Result<String> {
+        let entries : Vec<SearchEntry> = self.courses.iter()
+            .map(|course| SearchEntry {
+                course_code : &course.listing.course_code,
+                course_name : &course.listing.course_name,
+                url : format!("courses/{}.html", course.course_group_id),
+                text : format!("{} {} {}", course.listing.course_code, course.listing.course_name, course.description()).to_lowercase(),
+            })
+            .collect();
+
+        Ok(format!("window.SEARCH_INDEX = {};\n", serde_json::to_string(&entries)?))
+    }
+
+    fn department_course_counts(&self) -> HashMap<&str, usize> {
+        let mut course_counts = HashMap::new();
+        for course in &self.courses {
+            for department_code in &course.listing.department {
+                *course_counts.entry(department_code.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        course_counts
+    }
+}
+
+const SEARCH_BOX : &str = r#"<input id="search" type="search" placeholder="Search courses" autofocus>
+<ul id="results"></ul>
+<script src="search-index.js"></script>
+<script>
+const search = document.getElementById("search");
+const results = document.getElementById("results");
+search.addEventListener("input", () => {
+    const query = search.value.trim().toLowerCase();
+    results.replaceChildren();
+    if (query.length === 0) {
+        return;
+    }
+
+    for (const course of window.SEARCH_INDEX.filter((course) => course.text.includes(query)).slice(0, 50)) {
+        const link = document.createElement("a");
+        link.href = course.url;
+        link.textContent = `${course.course_code} ${course.course_name}`;
+        const item = document.createElement("li");
+        item.appendChild(link);
+        results.appendChild(item);
+    }
+});
+</script>
+"#;
+
+// `head` holds additional elements of the <head> (i.e. json-ld markup)
+fn html_layout(title : &str, head : &str, body : &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n\
+        <style>body {{ font-family : sans-serif; max-width : 50rem; margin : auto; padding : 1rem; }} \
+        table {{ border-collapse : collapse; }} td, th {{ border : 1px solid #ccc; padding : 0.25rem 0.5rem; }}</style>\n\
+{head}</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+// splits the text into plain text and mentions of courses that are part of the catalog
+fn link_course_codes<'t>(text : &'t str, course_group_ids_by_code : &'t HashMap<String, String>) -> Vec<Segment<'t>> {
+    let mut segments = Vec::new();
+    let mut plain_start = 0;
+    for mention in find_course_codes(text) {
+        let Some(course_group_id) = course_group_ids_by_code.get(&mention.course_code) else {
+            continue;
+        };
+
+        if plain_start < mention.range.start {
+            segments.push(Segment::Text(&text[plain_start..mention.range.start]));
+        }
+        plain_start = mention.range.end;
+        segments.push(Segment::Course { text : &text[mention.range], course_group_id });
+    }
+
+    if plain_start < text.len() {
+        segments.push(Segment::Text(&text[plain_start..]));
+    }
+
+    segments
+}
+
+// department names are lowercase within the mappings, i.e. "computer science" becomes "Computer Science"
+fn title_case(text : &str) -> String {
+    text.split(' ')
+        .map(|word| {
+            let mut characters = word.chars();
+            characters.next().map_or_else(String::new, |first| first.to_uppercase().chain(characters).collect())
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn escape_html(text : &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn escape_markdown(text : &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(character, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+
+    escaped
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::catalog_snapshot::tests::sample_snapshot;
+
+    // data structures, along with algorithms which lists it as a prerequisite
+    fn two_course_snapshot() -> CatalogSnapshot {
+        let mut snapshot = sample_snapshot();
+        let mut algorithms = snapshot.departments["CSC-CTY"][0].clone();
+        algorithms.course_group_id = String::from("0455461");
+        algorithms.course_code = String::from("CSC 22000");
+        algorithms.course_name = String::from("Algorithms");
+
+        let mut detail = snapshot.course_details["0455351"].clone();
+        detail.course_group_id = algorithms.course_group_id.clone();
+        detail.course_code = algorithms.course_code.clone();
+        detail.course_name = algorithms.course_name.clone();
+        detail.description = String::from("Prerequisite : CSc 21200 & CSC 10400 (not in the catalog)");
+
+        snapshot.departments.get_mut("CSC-CTY").unwrap().push(algorithms);
+        snapshot.course_details.insert(String::from("0455461"), detail);
+        snapshot
+    }
+
+    fn page<'p>(pages : &'p [SitePage], path : &str) -> &'p str {
+        &pages.iter().find(|page| page.path == Path::new(path)).unwrap().contents
+    }
+
+    #[test]
+    fn test_course_codes_are_linked() {
+        let course_group_ids_by_code = HashMap::from([(String::from("CSC 21200"), String::from("0455351"))]);
+        let segments = link_course_codes("Prerequisite : CSc21200, CSC 10400 or ABCSC 21200.", &course_group_ids_by_code);
+        assert_eq!(segments, vec![
+            Segment::Text("Prerequisite : "),
+            Segment::Course { text : "CSc21200", course_group_id : "0455351" },
+            Segment::Text(", CSC 10400 or ABCSC 21200."),
+        ]);
+    }
+
+    #[test]
+    fn test_html_site() {
+        let snapshot = two_course_snapshot();
+        let pages = SiteGenerator::new(&snapshot).render(SiteFormat::Html).unwrap();
+        assert_eq!(pages.len(), 5);
+
+        assert!(page(&pages, "index.html").contains("<a href=\"departments/CSC-CTY.html\">Computer Science</a> (2 courses)"));
+        assert!(page(&pages, "departments/CSC-CTY.html").contains("<a href=\"../courses/0455461.html\">CSC 22000 Algorithms</a>"));
+
+        let algorithms = page(&pages, "courses/0455461.html");
+        assert!(algorithms.contains("Prerequisite : <a href=\"0455351.html\">CSc 21200</a> &amp; CSC 10400"));
+        assert!(page(&pages, "courses/0455351.html").contains("<h2>Referenced by</h2>\n<ul>\n<li><a href=\"0455461.html\">CSC 22000 Algorithms</a></li>"));
+        assert!(algorithms.contains("<script type=\"application/ld+json\">{\"@context\":\"https://schema.org\",\"@type\":\"Course\",\"name\":\"Algorithms\""));
+        assert!(page(&pages, "search-index.js").contains("\"url\":\"courses/0455351.html\""));
+    }
+
+    #[test]
+    fn test_markdown_site() {
+        let snapshot = two_course_snapshot();
+        let pages = SiteGenerator::new(&snapshot).with_title("Catalog").render(SiteFormat::Markdown).unwrap();
+        assert_eq!(pages.len(), 4);
+
+        assert!(page(&pages, "index.md").starts_with("# Catalog\n"));
+        let algorithms = page(&pages, "courses/0455461.md");
+        assert!(algorithms.contains("Prerequisite : [CSc 21200](0455351.md) & CSC 10400"));
+        assert!(algorithms.contains("| LEC | 4 | 35 | In Person |"));
+    }
+}
@@ -0,0 +1,419 @@
+// sqlite export of the catalog, for running ad-hoc sql against it
+//
+// schema (every course is keyed by it's normalized course group id)
+// - departments (code, name)
+// - courses (one row per course, listing and detail fields)
+// - course_departments (courses are cross listed under several departments)
+// - components (one row per component of a course, ordered by position)
+// - credits (credits as listed, along with the parsed range i.e. "1-3" becomes 1.0 and 3.0)
+// - terms
+//
+// exports upsert rows, so a database can be refreshed by exporting into it again
+// courses that are no longer listed are kept, since the catalog is only ever added to
+use anyhow::{Context, Result};
+use crate::api::department::get_department_mappings;
+use crate::api::sans_io;
+use crate::export::course_text::parse_credit_range;
+use crate::models::{CourseComponents, CourseDetail, CourseInfo, Term};
+use crate::snapshot::CatalogSnapshot;
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+/// Version of the database schema, stored within sqlite's `user_version`.
+/// Databases created with a newer schema are rejected when opened.
+pub const SQLITE_SCHEMA_VERSION : i32 = 1;
+
+const SCHEMA : &str = "
+    CREATE TABLE IF NOT EXISTS departments (
+        code TEXT PRIMARY KEY,
+        name TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS courses (
+        course_group_id TEXT PRIMARY KEY,
+        unique_id TEXT NOT NULL,
+        course_code TEXT NOT NULL,
+        course_name TEXT NOT NULL,
+        long_name TEXT,
+        description TEXT,
+        subject_code TEXT NOT NULL,
+        course_number INTEGER,
+        career TEXT NOT NULL,
+        college TEXT,
+        effective_start_date TEXT,
+        effective_end_date TEXT,
+        typically_offered TEXT,
+        consent TEXT,
+        requirement_group TEXT,
+        status TEXT
+    );
+
+    CREATE INDEX IF NOT EXISTS courses_subject_code ON courses (subject_code);
+    CREATE INDEX IF NOT EXISTS courses_course_number ON courses (course_number);
+
+    CREATE TABLE IF NOT EXISTS course_departments (
+        course_group_id TEXT NOT NULL REFERENCES courses (course_group_id) ON DELETE CASCADE,
+        department_code TEXT NOT NULL REFERENCES departments (code),
+        PRIMARY KEY (course_group_id, department_code)
+    );
+
+    CREATE INDEX IF NOT EXISTS course_departments_department_code ON course_departments (department_code);
+
+    CREATE TABLE IF NOT EXISTS components (
+        course_group_id TEXT NOT NULL REFERENCES courses (course_group_id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        course_type TEXT NOT NULL,
+        attendance_type TEXT NOT NULL,
+        weekly_hours INTEGER NOT NULL,
+        class_size INTEGER NOT NULL,
+        final_exam TEXT NOT NULL,
+        exam_seat_spacing INTEGER NOT NULL,
+        instruction_mode TEXT NOT NULL,
+        PRIMARY KEY (course_group_id, position)
+    );
+
+    CREATE TABLE IF NOT EXISTS credits (
+        course_group_id TEXT PRIMARY KEY REFERENCES courses (course_group_id) ON DELETE CASCADE,
+        credits TEXT NOT NULL,
+        min_credits REAL,
+        max_credits REAL
+    );
+
+    CREATE TABLE IF NOT EXISTS terms (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        start_date TEXT NOT NULL,
+        end_date TEXT NOT NULL
+    );
+";
+
+/// Writes the catalog into a normalized sqlite database.
+///
+/// Exporting into an existing database updates the rows of courses and terms that were exported before,
+/// and adds the rest.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::{CatalogSnapshot, SqliteExporter};
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let snapshot = CatalogSnapshot::fetch(true).await?;
+///     let mut exporter = SqliteExporter::open("catalog.db")?;
+///     exporter.export_snapshot(&snapshot)?;
+///
+///     let course_count : i64 = exporter.connection().query_row("SELECT COUNT(*) FROM courses WHERE subject_code = 'CSC'", [], |row| row.get(0))?;
+///     println!("{course_count} computer science courses");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SqliteExporter {
+    connection : Connection
+}
+
+impl SqliteExporter {
+
+    /// Opens (or creates) the database at `path`, creating the tables that don't exist yet.
+    pub fn open(path : impl AsRef<Path>) -> Result<SqliteExporter> {
+        let path = path.as_ref();
+        let connection = Connection::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        SqliteExporter::with_connection(connection)
+    }
+
+    /// Same as [`SqliteExporter::open`], with a database that only exists in memory.
+    pub fn open_in_memory() -> Result<SqliteExporter> {
+        SqliteExporter::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection : Connection) -> Result<SqliteExporter> {
+        let schema_version : i32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if schema_version > SQLITE_SCHEMA_VERSION {
+            anyhow::bail!(
+                "database schema version {schema_version} is newer than the supported version {SQLITE_SCHEMA_VERSION}, please update the crate"
+            );
+        }
+
+        connection.pragma_update(None, "foreign_keys", true)?;
+        connection.execute_batch(SCHEMA)?;
+        connection.pragma_update(None, "user_version", SQLITE_SCHEMA_VERSION)?;
+
+        // every known department is present, even before it's courses are exported
+        let mut statement = connection.prepare(
+            "INSERT INTO departments (code, name) VALUES (?1, ?2)
+             ON CONFLICT (code) DO UPDATE SET name = excluded.name"
+        )?;
+        for (department_name, department_code) in get_department_mappings() {
+            statement.execute(params![department_code, department_name])?;
+        }
+        drop(statement);
+
+        Ok(SqliteExporter { connection })
+    }
+
+    /// Exports every listing, course detail and term of the snapshot within a single transaction.
+    pub fn export_snapshot(&mut self, snapshot : &CatalogSnapshot) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        for courses in snapshot.departments.values() {
+            upsert_courses(&transaction, courses)?;
+        }
+
+        upsert_course_details(&transaction, snapshot.course_details.values())?;
+        upsert_terms(&transaction, &snapshot.terms)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Exports course listings (i.e. the result of [`crate::CCNYCourseCatalog::get_courses_list`]).
+    ///
+    /// Listings only hold the first component of a course, the remaining components
+    /// and detail fields (i.e. the description) of previously exported details are kept.
+    pub fn export_courses(&mut self, courses : &[CourseInfo]) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        upsert_courses(&transaction, courses)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Exports course details, replacing the components of the courses.
+    pub fn export_course_details(&mut self, course_details : &[CourseDetail]) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        upsert_course_details(&transaction, course_details)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    pub fn export_terms(&mut self, terms : &[Term]) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        upsert_terms(&transaction, terms)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Connection to the database, for querying what was exported.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+fn upsert_courses(connection : &Connection, courses : &[CourseInfo]) -> Result<()> {
+    let mut course_statement = connection.prepare_cached(
+        "INSERT INTO courses (
+            course_group_id, unique_id, course_code, course_name, subject_code, course_number, career,
+            effective_start_date, effective_end_date
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT (course_group_id) DO UPDATE SET
+            unique_id = excluded.unique_id,
+            course_code = excluded.course_code,
+            course_name = excluded.course_name,
+            subject_code = excluded.subject_code,
+            course_number = excluded.course_number,
+            career = excluded.career,
+            effective_start_date = excluded.effective_start_date,
+            effective_end_date = excluded.effective_end_date"
+    )?;
+
+    for course in courses {
+        let course_group_id = sans_io::normalize_course_group_id(&course.course_group_id);
+        course_statement.execute(params![
+            course_group_id,
+            course.unique_id,
+            course.course_code,
+            course.course_name,
+            course.subject_code,
+            course.course_number,
+            course.career,
+            course.effective_start_date,
+            course.effective_end_date,
+        ])?;
+
+        upsert_component(connection, &course_group_id, 0, &course.course_components)?;
+        replace_departments(connection, &course_group_id, &course.department)?;
+        upsert_credits(connection, &course_group_id, &course.credits)?;
+    }
+
+    Ok(())
+}
+
+fn upsert_course_details<'a>(connection : &Connection, course_details : impl IntoIterator<Item = &'a CourseDetail>) -> Result<()> {
+    // the course number of a listing is kept if the one of the detail isn't numeric
+    let mut course_statement = connection.prepare_cached(
+        "INSERT INTO courses (
+            course_group_id, unique_id, course_code, course_name, long_name, description, subject_code, course_number,
+            career, college, typically_offered, consent, requirement_group, status
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+        ON CONFLICT (course_group_id) DO UPDATE SET
+            unique_id = excluded.unique_id,
+            course_code = excluded.course_code,
+            course_name = excluded.course_name,
+            long_name = excluded.long_name,
+            description = excluded.description,
+            subject_code = excluded.subject_code,
+            course_number = COALESCE(excluded.course_number, courses.course_number),
+            career = excluded.career,
+            college = excluded.college,
+            typically_offered = excluded.typically_offered,
+            consent = excluded.consent,
+            requirement_group = excluded.requirement_group,
+            status = excluded.status"
+    )?;
+
+    for detail in course_details {
+        let course_group_id = sans_io::normalize_course_group_id(&detail.course_group_id);
+        course_statement.execute(params![
+            course_group_id,
+            detail.unique_id,
+            detail.course_code,
+            detail.course_name,
+            detail.long_name,
+            detail.description,
+            detail.subject_code,
+            detail.course_number.trim().parse::<i64>().ok(),
+            detail.career,
+            detail.college,
+            detail.typically_offered,
+            detail.consent,
+            detail.requirement_group,
+            detail.status,
+        ])?;
+
+        connection.execute("DELETE FROM components WHERE course_group_id = ?1", params![course_group_id])?;
+        for (position, component) in detail.course_components.iter().enumerate() {
+            upsert_component(connection, &course_group_id, position, component)?;
+        }
+
+        replace_departments(connection, &course_group_id, &detail.departments)?;
+        upsert_credits(connection, &course_group_id, &detail.credits)?;
+    }
+
+    Ok(())
+}
+
+fn upsert_component(connection : &Connection, course_group_id : &str, position : usize, component : &CourseComponents) -> Result<()> {
+    connection.prepare_cached(
+        "INSERT INTO components (
+            course_group_id, position, course_type, attendance_type, weekly_hours, class_size, final_exam,
+            exam_seat_spacing, instruction_mode
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT (course_group_id, position) DO UPDATE SET
+            course_type = excluded.course_type,
+            attendance_type = excluded.attendance_type,
+            weekly_hours = excluded.weekly_hours,
+            class_size = excluded.class_size,
+            final_exam = excluded.final_exam,
+            exam_seat_spacing = excluded.exam_seat_spacing,
+            instruction_mode = excluded.instruction_mode"
+    )?.execute(params![
+        course_group_id,
+        position as i64,
+        component.course_type,
+        component.attendance_type,
+        component.weekly_hours,
+        component.class_size,
+        component.final_exam,
+        component.exam_seat_spacing,
+        component.instruction_mode,
+    ])?;
+
+    Ok(())
+}
+
+// the departments a course is listed under may change between exports
+fn replace_departments(connection : &Connection, course_group_id : &str, department_codes : &[String]) -> Result<()> {
+    connection.execute("DELETE FROM course_departments WHERE course_group_id = ?1", params![course_group_id])?;
+    for department_code in department_codes {
+        // departments missing from the mappings are added without a name
+        connection.prepare_cached("INSERT OR IGNORE INTO departments (code, name) VALUES (?1, NULL)")?
+            .execute(params![department_code])?;
+        connection.prepare_cached("INSERT OR IGNORE INTO course_departments (course_group_id, department_code) VALUES (?1, ?2)")?
+            .execute(params![course_group_id, department_code])?;
+    }
+
+    Ok(())
+}
+
+fn upsert_credits(connection : &Connection, course_group_id : &str, credits : &str) -> Result<()> {
+    let (min_credits, max_credits) = parse_credit_range(credits).unzip();
+    connection.prepare_cached(
+        "INSERT INTO credits (course_group_id, credits, min_credits, max_credits) VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT (course_group_id) DO UPDATE SET
+            credits = excluded.credits,
+            min_credits = excluded.min_credits,
+            max_credits = excluded.max_credits"
+    )?.execute(params![course_group_id, credits, min_credits, max_credits])?;
+
+    Ok(())
+}
+
+fn upsert_terms(connection : &Connection, terms : &[Term]) -> Result<()> {
+    let mut statement = connection.prepare_cached(
+        "INSERT INTO terms (id, name, start_date, end_date) VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT (id) DO UPDATE SET
+            name = excluded.name,
+            start_date = excluded.start_date,
+            end_date = excluded.end_date"
+    )?;
+
+    for term in terms {
+        statement.execute(params![term.id, term.name, term.start_date, term.end_date])?;
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::catalog_snapshot::tests::sample_snapshot;
+
+    fn count(exporter : &SqliteExporter, query : &str) -> i64 {
+        exporter.connection().query_row(query, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_export() {
+        let mut snapshot = sample_snapshot();
+        let detail = snapshot.course_details.get_mut("0455351").unwrap();
+        detail.subject_code = String::from("CSC");
+        detail.course_number = String::from("21200");
+        let lab = CourseComponents { course_type : String::from("LAB"), ..detail.course_components[0].clone() };
+        detail.course_components.push(lab);
+
+        let mut exporter = SqliteExporter::open_in_memory().unwrap();
+        exporter.export_snapshot(&snapshot).unwrap();
+
+        assert_eq!(count(&exporter, "SELECT COUNT(*) FROM courses WHERE subject_code = 'CSC' AND course_number = 21200"), 1);
+        assert_eq!(count(&exporter, "SELECT COUNT(*) FROM components WHERE course_group_id = '0455351'"), 2);
+        assert_eq!(count(&exporter, "SELECT COUNT(*) FROM terms"), 1);
+        assert_eq!(
+            count(&exporter, "SELECT COUNT(*) FROM course_departments JOIN departments ON departments.code = department_code WHERE name = 'computer science'"),
+            1
+        );
+        assert_eq!(count(&exporter, "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name LIKE 'courses_%'"), 2);
+    }
+
+    #[test]
+    fn test_exports_are_upserted() {
+        let mut exporter = SqliteExporter::open_in_memory().unwrap();
+        exporter.export_snapshot(&sample_snapshot()).unwrap();
+
+        // the listing changes, the description (only known from the detail) is kept
+        let mut courses = sample_snapshot().departments["CSC-CTY"].clone();
+        courses[0].credits = String::from("3 - 4");
+        courses[0].course_group_id = String::from("455351");
+        exporter.export_courses(&courses).unwrap();
+
+        assert_eq!(count(&exporter, "SELECT COUNT(*) FROM courses"), 1);
+        assert_eq!(count(&exporter, "SELECT COUNT(*) FROM courses WHERE description LIKE 'Extension%'"), 1);
+        assert_eq!(count(&exporter, "SELECT COUNT(*) FROM credits WHERE min_credits = 3 AND max_credits = 4"), 1);
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.pragma_update(None, "user_version", SQLITE_SCHEMA_VERSION + 1).unwrap();
+        let error = SqliteExporter::with_connection(connection).unwrap_err();
+        assert!(error.to_string().contains("newer than the supported version"));
+    }
+}
@@ -9,10 +9,51 @@ mod models;
 mod data_structures;
 mod api;
 mod ccny_course_catalog;
-// pub use data_structures::{custom_hashmap, custom_lru_cache};
+mod snapshot;
+mod export;
+mod schema;
+
+/// Synchronous wrapper around [`CCNYCourseCatalog`] for callers without an async runtime.
+pub mod blocking;
+
+/// Hashmap and LRU cache implementations used internally, exposed for reuse.
+pub use data_structures::{concurrent_cache, custom_hashmap, custom_lru_cache};
 
 /// All relevant implementation can be found here.
 pub use crate::ccny_course_catalog::CCNYCourseCatalog;
-// we use this line if pub use self::course::{CourseInfo, CourseComponents}; 
-// is not included within mod.rs
-use models::course::{CourseComponents, CourseInfo};
\ No newline at end of file
+
+/// Configuration of the in-memory response cache.
+pub use crate::api::cache::CacheConfig;
+
+/// Configuration of the optional on-disk response cache.
+pub use crate::api::disk_cache::DiskCacheConfig;
+
+/// Saved copies of the catalog and the offline catalog that queries them.
+pub use crate::snapshot::{CatalogSnapshot, OfflineCatalog, SNAPSHOT_FORMAT_VERSION};
+
+/// Changes between two catalog snapshots.
+pub use crate::snapshot::{CatalogDiff, CourseChange, CourseSummary, FieldChange};
+
+/// Csv export of course listings and course details.
+pub use crate::export::{CourseColumn, CourseDetailColumn, CsvColumn, CsvExporter, CsvRecord};
+
+/// schema.org Course markup (json-ld).
+pub use crate::export::{CourseJsonLd, CoursePrerequisite, JsonLdOrganization};
+
+/// Newline delimited json export, streamed as the catalog is downloaded.
+pub use crate::export::{NdjsonCompression, NdjsonWriter};
+
+/// Static html/markdown copy of the catalog.
+pub use crate::export::{SiteFormat, SiteGenerator, SitePage};
+
+/// Sqlite export of the catalog.
+pub use crate::export::{SqliteExporter, SQLITE_SCHEMA_VERSION};
+
+/// Json schema documents of the models, and the version embedded within exports.
+pub use crate::schema::{breaking_schema_changes, model_schemas, write_model_schemas, MODEL_SCHEMA_VERSION};
+
+/// Request builders and response parsers that are independent of any HTTP client.
+pub use crate::api::sans_io;
+
+/// Models returned by the catalog.
+pub use models::{CourseComponents, CourseDetail, CourseInfo, Term};
@@ -1,10 +1,11 @@
 // Stores all relevant structs here
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // redefining CourseComponents here, could throw potential error
 /// Represents the components of a course such as lecture, lab, etc.
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct CourseComponents {
     pub course_type : String,
     pub attendance_type : String,
@@ -16,7 +17,7 @@ pub struct CourseComponents {
 }
 
 /// Main course information structure
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct CourseInfo {
     pub unique_id : String,
     pub course_name : String,
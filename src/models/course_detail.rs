@@ -0,0 +1,28 @@
+// Stores the structs returned when retrieving information about a particular course
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::CourseComponents;
+
+/// Detailed information about a single course, as returned by the course search endpoint
+/// when it's queried for a specific `courseGroupId`.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct CourseDetail {
+    pub unique_id : String,
+    pub course_group_id : String,
+    pub course_code : String,           // (i.e. CSC 21200)
+    pub course_name : String,
+    pub long_name : String,
+    pub description : String,
+    pub career : String,
+    pub college : String,
+    pub subject_code : String,
+    pub course_number : String,
+    pub credits : String,
+    pub course_components : Vec<CourseComponents>,
+    pub typically_offered : String,     // (i.e. "Fall, Spring")
+    pub consent : String,
+    pub departments : Vec<String>,      // department ids (i.e. CSC-CTY)
+    pub requirement_group : String,
+    pub status : String
+}
@@ -0,0 +1,13 @@
+// Stores the struct representing an academic term
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// An academic term (i.e. "2024 Fall Term") as returned by the terms endpoint.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct Term {
+    pub id : String,
+    pub name : String,
+    pub start_date : String,
+    pub end_date : String
+}
@@ -0,0 +1,218 @@
+// json schema documents of the models, for consumers of the exported json written in other languages
+//
+// the schemas are committed under schemas/ and compared against the generated ones by the tests below,
+// so that changes to the models are noticed before they reach consumers
+// - regenerating the committed schemas : UPDATE_SCHEMAS=1 cargo test schema
+// - breaking changes (i.e. a removed or renamed field) also require MODEL_SCHEMA_VERSION to be bumped
+use anyhow::{Context, Result};
+use crate::models::{CourseComponents, CourseDetail, CourseInfo, Term};
+use crate::snapshot::{CatalogDiff, CatalogSnapshot};
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Version of the json representation of the models, embedded within exports
+/// (i.e. every record written by [`crate::NdjsonWriter`]) and within the generated schemas.
+///
+/// Bumped whenever a model changes in a way that breaks existing consumers.
+pub const MODEL_SCHEMA_VERSION : u32 = 1;
+
+// keyword holding MODEL_SCHEMA_VERSION within the generated schemas
+const VERSION_KEYWORD : &str = "x-schema-version";
+
+/// JSON Schema (draft 2020-12) of every public model, along with the name of the model (i.e. "course_info").
+pub fn model_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("course_info", versioned_schema::<CourseInfo>()),
+        ("course_components", versioned_schema::<CourseComponents>()),
+        ("course_detail", versioned_schema::<CourseDetail>()),
+        ("term", versioned_schema::<Term>()),
+        ("catalog_snapshot", versioned_schema::<CatalogSnapshot>()),
+        ("catalog_diff", versioned_schema::<CatalogDiff>()),
+    ]
+}
+
+/// Writes the schemas returned by [`model_schemas`] into `directory`, as `{name}.schema.json`.
+pub fn write_model_schemas(directory : impl AsRef<Path>) -> Result<()> {
+    let directory = directory.as_ref();
+    std::fs::create_dir_all(directory).with_context(|| format!("failed to create {}", directory.display()))?;
+    for (name, schema) in model_schemas() {
+        let path = directory.join(format!("{name}.schema.json"));
+        let json = serde_json::to_string_pretty(&schema)? + "\n";
+        std::fs::write(&path, json).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Changes between two versions of a schema that break consumers reading json written with the `newer` one,
+/// i.e. "$.credits : removed" or "$.course_number : type changed from \"integer\" to \"string\"".
+///
+/// Adding fields isn't considered a breaking change.
+pub fn breaking_schema_changes(older : &Value, newer : &Value) -> Vec<String> {
+    let mut comparison = SchemaComparison {
+        older_root : older,
+        newer_root : newer,
+        compared_references : HashSet::new(),
+        changes : Vec::new(),
+    };
+
+    comparison.compare("$", older, newer);
+    comparison.changes
+}
+
+fn versioned_schema<T : JsonSchema>() -> Value {
+    let mut schema = schemars::schema_for!(T);
+    schema.insert(VERSION_KEYWORD.to_owned(), MODEL_SCHEMA_VERSION.into());
+    schema.to_value()
+}
+
+struct SchemaComparison<'a> {
+    older_root : &'a Value,
+    newer_root : &'a Value,
+    compared_references : HashSet<(String, String)>,    // avoids comparing recursive definitions forever
+    changes : Vec<String>,
+}
+
+impl<'a> SchemaComparison<'a> {
+    fn compare(&mut self, path : &str, older : &'a Value, newer : &'a Value) {
+        let references = (reference(older), reference(newer));
+        if let (Some(older_reference), Some(newer_reference)) = references {
+            if !self.compared_references.insert((older_reference.to_owned(), newer_reference.to_owned())) {
+                return;
+            }
+        }
+
+        let older = resolve(self.older_root, older);
+        let newer = resolve(self.newer_root, newer);
+
+        if older.get("type") != newer.get("type") {
+            self.changes.push(format!(
+                "{path} : type changed from {} to {}",
+                older.get("type").unwrap_or(&Value::Null), newer.get("type").unwrap_or(&Value::Null)
+            ));
+            return;
+        }
+
+        if let Some(older_properties) = older.get("properties").and_then(Value::as_object) {
+            let newer_properties = newer.get("properties").and_then(Value::as_object);
+            for (name, older_property) in older_properties {
+                match newer_properties.and_then(|properties| properties.get(name)) {
+                    Some(newer_property) => self.compare(&format!("{path}.{name}"), older_property, newer_property),
+                    None => self.changes.push(format!("{path}.{name} : removed")),
+                }
+            }
+        }
+
+        // fields that may now be missing break consumers relying on them
+        let newer_required = required(newer);
+        for name in required(older) {
+            if !newer_required.contains(&name) && newer.pointer(&format!("/properties/{name}")).is_some() {
+                self.changes.push(format!("{path}.{name} : no longer required"));
+            }
+        }
+
+        if let (Some(older_items), Some(newer_items)) = (older.get("items"), newer.get("items")) {
+            self.compare(&format!("{path}[]"), older_items, newer_items);
+        }
+
+        // values of maps (i.e. the departments of a snapshot)
+        if let (Some(older_values @ Value::Object(_)), Some(newer_values @ Value::Object(_))) = (older.get("additionalProperties"), newer.get("additionalProperties")) {
+            self.compare(&format!("{path}{{*}}"), older_values, newer_values);
+        }
+    }
+}
+
+fn reference(schema : &Value) -> Option<&str> {
+    schema.get("$ref").and_then(Value::as_str)
+}
+
+// follows local references (i.e. "#/$defs/CourseComponents") to their definition
+fn resolve<'a>(root : &'a Value, schema : &'a Value) -> &'a Value {
+    reference(schema)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+        .unwrap_or(schema)
+}
+
+fn required(schema : &Value) -> Vec<&str> {
+    schema.get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn schema_directory() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas")
+    }
+
+    #[test]
+    fn test_committed_schemas_are_compatible() {
+        let update_schemas = std::env::var_os("UPDATE_SCHEMAS").is_some();
+        for (name, schema) in model_schemas() {
+            let path = schema_directory().join(format!("{name}.schema.json"));
+            let Ok(json) = std::fs::read_to_string(&path) else {
+                assert!(update_schemas, "{} is missing, generate it with UPDATE_SCHEMAS=1 cargo test schema", path.display());
+                continue;
+            };
+            let committed : Value = serde_json::from_str(&json).unwrap();
+
+            // checked before regenerating, so that breaking changes can't be committed without bumping the version
+            let breaking_changes = breaking_schema_changes(&committed, &schema);
+            if committed[VERSION_KEYWORD] == MODEL_SCHEMA_VERSION {
+                assert!(
+                    breaking_changes.is_empty(),
+                    "breaking changes to the {name} schema, bump MODEL_SCHEMA_VERSION and regenerate the schemas : {breaking_changes:#?}"
+                );
+            }
+
+            if !update_schemas {
+                assert_eq!(committed, schema, "the {name} schema changed, regenerate it with UPDATE_SCHEMAS=1 cargo test schema");
+            }
+        }
+
+        if update_schemas {
+            write_model_schemas(schema_directory()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_breaking_changes() {
+        let older = serde_json::json!({
+            "type" : "object",
+            "properties" : {
+                "course_code" : { "type" : "string" },
+                "course_number" : { "type" : "integer" },
+                "credits" : { "type" : "string" },
+                "course_components" : { "$ref" : "#/$defs/CourseComponents" }
+            },
+            "required" : ["course_code", "course_number", "credits", "course_components"],
+            "$defs" : {
+                "CourseComponents" : { "type" : "object", "properties" : { "class_size" : { "type" : "integer" } } }
+            }
+        });
+
+        // adding fields doesn't break anything
+        let mut newer = older.clone();
+        newer["properties"]["long_name"] = serde_json::json!({ "type" : "string" });
+        assert!(breaking_schema_changes(&older, &newer).is_empty());
+
+        newer["properties"]["course_number"]["type"] = "string".into();
+        newer["properties"].as_object_mut().unwrap().remove("credits");
+        newer["required"] = serde_json::json!(["course_number", "course_components"]);
+        newer["$defs"]["CourseComponents"]["properties"]["class_size"]["type"] = serde_json::json!(["integer", "null"]);
+        assert_eq!(breaking_schema_changes(&older, &newer), vec![
+            "$.course_components.class_size : type changed from \"integer\" to [\"integer\",\"null\"]",
+            "$.course_number : type changed from \"integer\" to \"string\"",
+            "$.credits : removed",
+            "$.course_code : no longer required",
+        ]);
+    }
+}
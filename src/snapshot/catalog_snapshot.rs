@@ -0,0 +1,271 @@
+// versioned format of a saved catalog
+// department listings, course details and terms are stored along with the time they were downloaded at
+//
+// BTreeMaps are used so that the saved files are ordered consistently, making them easy to compare
+use anyhow::{Context, Result};
+use crate::api::course_finder::{retrieve_course_info_by_group_id, retrieve_historical_terms};
+use crate::api::department::{coursedog_base_url, fetch_courses_by_department_helper, get_department_mappings};
+use crate::api::sans_io::{self, DEFAULT_CATALOG_ID, DEFAULT_EFFECTIVE_DATES_RANGE};
+use crate::models::{CourseDetail, CourseInfo, Term};
+use crate::schema::MODEL_SCHEMA_VERSION;
+use futures::stream::{self, Stream, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the snapshot format written by this version of the crate.
+/// Snapshots written with a newer format are rejected when loaded.
+pub const SNAPSHOT_FORMAT_VERSION : u32 = 1;
+
+// number of requests sent at the same time while downloading a snapshot
+const CONCURRENT_REQUESTS : usize = 4;
+
+/// Everything the catalog knows about at a point in time.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::CatalogSnapshot;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let snapshot = CatalogSnapshot::fetch(true).await?;
+///     snapshot.save("catalog.json")?;
+///
+///     let snapshot = CatalogSnapshot::load("catalog.json")?;
+///     println!("{} departments", snapshot.departments.len());
+///     Ok(())
+/// }
+/// ```
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct CatalogSnapshot {
+    pub format_version : u32,
+    #[serde(default)]
+    pub schema_version : u32,                               // MODEL_SCHEMA_VERSION of the models, 0 for snapshots written before it existed
+    pub fetched_at : u64,                                   // seconds since the unix epoch
+    pub catalog_id : String,
+    pub effective_dates_range : String,
+    pub departments : BTreeMap<String, Vec<CourseInfo>>,    // department id (i.e. CSC-CTY) to it's courses
+    #[serde(default)]
+    pub failed_departments : BTreeMap<String, String>,      // department id to the error met while downloading it's courses
+    pub course_details : BTreeMap<String, CourseDetail>,    // course group id (i.e. 0455351) to it's details
+    #[serde(default)]
+    pub failed_course_details : BTreeMap<String, String>,   // course group id to the error met while downloading it's details
+    pub terms : Vec<Term>,
+    #[serde(default)]
+    pub failed_terms : Option<String>                       // error met while downloading the terms, if any
+}
+
+impl CatalogSnapshot {
+
+    /// Empty snapshot of the current catalog, timestamped with the current time.
+    pub fn new() -> Self {
+        CatalogSnapshot {
+            format_version : SNAPSHOT_FORMAT_VERSION,
+            schema_version : MODEL_SCHEMA_VERSION,
+            fetched_at : SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs()),
+            catalog_id : DEFAULT_CATALOG_ID.to_owned(),
+            effective_dates_range : DEFAULT_EFFECTIVE_DATES_RANGE.to_owned(),
+            ..CatalogSnapshot::default()
+        }
+    }
+
+    /// Downloads every department listing and the terms.
+    /// - `include_course_details` : also downloads the details of every course, which requires one request per course
+    ///
+    /// Departments, course details and terms that fail to download don't fail the snapshot,
+    /// they are recorded within `failed_departments`, `failed_course_details` and `failed_terms` instead.
+    pub async fn fetch(include_course_details : bool) -> Result<CatalogSnapshot> {
+        CatalogSnapshot::fetch_from(&coursedog_base_url(), include_course_details).await
+    }
+
+    /// Same as [`CatalogSnapshot::fetch`], downloading from another Coursedog API (i.e. the `coursedog-mock` stand-in server).
+    pub async fn fetch_from(base_url : &str, include_course_details : bool) -> Result<CatalogSnapshot> {
+        let mut snapshot = CatalogSnapshot::new();
+        let mut listings = std::pin::pin!(department_listings(base_url));
+        while let Some((department_code, courses)) = listings.next().await {
+            match courses {
+                Ok(courses) => { snapshot.departments.insert(department_code, courses); },
+                Err(err) => { snapshot.failed_departments.insert(department_code, format!("{err:#}")); },
+            }
+        }
+
+        if include_course_details {
+            // courses may be cross listed under several departments, each is only downloaded once
+            let course_group_ids : Vec<String> = snapshot.courses()
+                .map(|course| sans_io::normalize_course_group_id(&course.course_group_id))
+                .collect::<std::collections::BTreeSet<String>>()
+                .into_iter()
+                .collect();
+
+            let mut details = std::pin::pin!(course_details(base_url, course_group_ids));
+            while let Some((course_group_id, detail)) = details.next().await {
+                match detail {
+                    Ok(Some(detail)) => { snapshot.course_details.insert(course_group_id, detail); },
+                    Ok(None) => {},
+                    Err(err) => { snapshot.failed_course_details.insert(course_group_id, format!("{err:#}")); },
+                }
+            }
+        }
+
+        match retrieve_historical_terms(base_url).await {
+            Ok(terms) => snapshot.terms = terms,
+            Err(err) => snapshot.failed_terms = Some(format!("{err:#}")),
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Reads a snapshot from a json string, rejecting snapshots written with a newer format.
+    pub fn from_json(json : &str) -> Result<CatalogSnapshot> {
+        // the version is checked before the rest, a newer format may not deserialize into the current one
+        #[derive(Deserialize)]
+        struct Version {
+            format_version : u32,
+        }
+
+        let version : Version = serde_json::from_str(json).context("not a catalog snapshot")?;
+        if version.format_version > SNAPSHOT_FORMAT_VERSION {
+            anyhow::bail!(
+                "snapshot format version {} is newer than the supported version {SNAPSHOT_FORMAT_VERSION}, please update the crate",
+                version.format_version
+            );
+        }
+
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reads a snapshot previously written by [`CatalogSnapshot::save`].
+    pub fn load(path : impl AsRef<Path>) -> Result<CatalogSnapshot> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+        CatalogSnapshot::from_json(&json)
+    }
+
+    pub fn save(&self, path : impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Every course of every department, courses listed under several departments are repeated.
+    pub fn courses(&self) -> impl Iterator<Item = &CourseInfo> {
+        self.departments.values().flatten()
+    }
+}
+
+// listings of every department (ordered by department code), downloading a few departments at a time
+// failures are reported per department, so that callers can decide whether to skip them
+pub(crate) fn department_listings(base_url : &str) -> impl Stream<Item = (String, Result<Vec<CourseInfo>>)> {
+    let mut department_codes : Vec<String> = get_department_mappings().into_values().collect();
+    department_codes.sort();
+
+    let base_url = base_url.to_owned();
+    stream::iter(department_codes)
+        .map(move |department_code| {
+            let base_url = base_url.clone();
+            async move {
+                let courses = async {
+                    let response = fetch_courses_by_department_helper(&base_url, &department_code).await?;
+                    sans_io::department_courses_from_value(&response)
+                        .with_context(|| format!("failed to parse the courses of {department_code}"))
+                }.await;
+                (department_code, courses)
+            }
+        })
+        .buffered(CONCURRENT_REQUESTS)
+}
+
+// details of the provided courses (in the same order), None for courses without details
+// failures are reported per course, the same way as department_listings
+pub(crate) fn course_details(base_url : &str, course_group_ids : Vec<String>) -> impl Stream<Item = (String, Result<Option<CourseDetail>>)> {
+    let base_url = base_url.to_owned();
+    stream::iter(course_group_ids)
+        .map(move |course_group_id| {
+            let base_url = base_url.clone();
+            async move {
+                let detail = async {
+                    let response = retrieve_course_info_by_group_id(&base_url, &course_group_id).await?;
+                    let details = sans_io::course_details_from_value(&response)?;
+                    Ok(details.into_iter().next())
+                }.await;
+                (course_group_id, detail)
+            }
+        })
+        .buffered(CONCURRENT_REQUESTS)
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn sample_snapshot() -> CatalogSnapshot {
+        let course : CourseInfo = serde_json::from_value(serde_json::json!({
+            "unique_id" : "0455351-1901-01-01",
+            "course_name" : "Data Structures",
+            "career" : "Undergraduate",
+            "course_code" : "CSC 21200",
+            "course_components" : {
+                "course_type" : "LEC",
+                "attendance_type" : "Class Meeting",
+                "weekly_hours" : 4,
+                "class_size" : 35,
+                "final_exam" : "Yes",
+                "exam_seat_spacing" : 1,
+                "instruction_mode" : "In Person"
+            },
+            "effective_start_date" : "1901-01-01",
+            "effective_end_date" : "unknown",
+            "course_group_id" : "0455351",
+            "course_number" : 21200,
+            "department" : ["CSC-CTY"],
+            "subject_code" : "CSC",
+            "credits" : "3"
+        })).unwrap();
+
+        let detail = CourseDetail {
+            unique_id : course.unique_id.clone(),
+            course_group_id : course.course_group_id.clone(),
+            course_code : course.course_code.clone(),
+            course_name : course.course_name.clone(),
+            description : String::from("Extension of the knowledge of algorithm design and programming gained in CSC 10300"),
+            credits : course.credits.clone(),
+            course_components : vec![course.course_components.clone()],
+            departments : course.department.clone(),
+            ..CourseDetail::default()
+        };
+
+        let mut snapshot = CatalogSnapshot::new();
+        snapshot.departments.insert(String::from("CSC-CTY"), vec![course]);
+        snapshot.course_details.insert(String::from("0455351"), detail);
+        snapshot.terms.push(Term { id : String::from("1249"), name : String::from("2024 Fall Term"), ..Term::default() });
+        snapshot
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let snapshot = sample_snapshot();
+        let path = std::env::temp_dir().join(format!("ccny-snapshot-{}.json", std::process::id()));
+        snapshot.save(&path).unwrap();
+        let loaded = CatalogSnapshot::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, snapshot);
+        assert_eq!(loaded.courses().count(), 1);
+    }
+
+    #[test]
+    fn test_newer_format_is_rejected() {
+        let mut snapshot = sample_snapshot();
+        snapshot.format_version = SNAPSHOT_FORMAT_VERSION + 1;
+        let error = CatalogSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("newer than the supported version"));
+
+        assert!(CatalogSnapshot::from_json("{\"departments\" : {}}").is_err());
+    }
+}
@@ -0,0 +1,327 @@
+// compares two snapshots of the catalog (i.e. taken at the start of two semesters)
+// courses are matched by their course group id, which stays the same when a course is renamed or renumbered
+use crate::api::sans_io;
+use crate::models::{CourseComponents, CourseDetail, CourseInfo};
+use crate::snapshot::CatalogSnapshot;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Changes between two [`CatalogSnapshot`]s, courses are ordered by course group id.
+///
+/// `Display` renders a human readable report, the struct itself serializes into json.
+#[derive(Serialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct CatalogDiff {
+    pub added : Vec<CourseSummary>,
+    pub removed : Vec<CourseSummary>,
+    pub modified : Vec<CourseChange>
+}
+
+/// Identifies a course within a [`CatalogDiff`].
+#[derive(Serialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct CourseSummary {
+    pub course_group_id : String,
+    pub course_code : String,           // (i.e. CSC 21200)
+    pub course_name : String
+}
+
+/// Course present in both snapshots, with at least one changed field.
+#[derive(Serialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct CourseChange {
+    pub course : CourseSummary,         // as it appears within the newer snapshot
+    pub changes : Vec<FieldChange>
+}
+
+/// Value of a field before and after the change.
+#[derive(Serialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct FieldChange {
+    pub field : String,                 // (i.e. "credits")
+    pub before : String,
+    pub after : String
+}
+
+impl CatalogDiff {
+
+    /// Compares the `older` snapshot with the `newer` one.
+    ///
+    /// Fields that only come from course details (i.e. the description) are only compared
+    /// if the details of the course are present within both snapshots.
+    ///
+    /// Courses of departments that failed to download within one of the snapshots aren't reported as added or removed.
+    pub fn between(older : &CatalogSnapshot, newer : &CatalogSnapshot) -> CatalogDiff {
+        let older_courses = course_fields(older);
+        let mut newer_courses = course_fields(newer);
+        let mut diff = CatalogDiff::default();
+
+        for (course_group_id, older_fields) in older_courses {
+            let Some(newer_fields) = newer_courses.remove(&course_group_id) else {
+                if !listed_in_failed_department(older, newer, &course_group_id) {
+                    diff.removed.push(summary(&course_group_id, &older_fields));
+                }
+                continue;
+            };
+
+            // BTreeMap iteration keeps the order of the fields stable
+            let changes : Vec<FieldChange> = older_fields.iter()
+                .filter_map(|(field, before)| {
+                    let after = newer_fields.get(field)?;
+                    (before != after).then(|| FieldChange {
+                        field : field.to_string(),
+                        before : before.clone(),
+                        after : after.clone(),
+                    })
+                })
+                .collect();
+
+            if !changes.is_empty() {
+                diff.modified.push(CourseChange {
+                    course : summary(&course_group_id, &newer_fields),
+                    changes,
+                });
+            }
+        }
+
+        // whatever remains only exists within the newer snapshot
+        diff.added = newer_courses.iter()
+            .filter(|(course_group_id, _)| !listed_in_failed_department(newer, older, course_group_id))
+            .map(|(course_group_id, fields)| summary(course_group_id, fields))
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl CatalogSnapshot {
+    /// Same as [`CatalogDiff::between`], comparing this snapshot with a `newer` one.
+    pub fn diff(&self, newer : &CatalogSnapshot) -> CatalogDiff {
+        CatalogDiff::between(self, newer)
+    }
+}
+
+impl fmt::Display for CourseSummary {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({})", self.course_code, self.course_name, self.course_group_id)
+    }
+}
+
+// Sample output:
+// 1 added, 1 removed, 1 modified
+//
+// Added:
+//   + CSC 21300 Data Structures II (0455352)
+//
+// Removed:
+//   - CSC 44200 Systems Simulation (0455781)
+//
+// Modified:
+//   ~ CSC 21200 Data Structures (0455351)
+//       credits : 3 -> 4
+impl fmt::Display for CatalogDiff {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        writeln!(f, "{} added, {} removed, {} modified", self.added.len(), self.removed.len(), self.modified.len())?;
+
+        if !self.added.is_empty() {
+            writeln!(f, "\nAdded:")?;
+            for course in &self.added {
+                writeln!(f, "  + {course}")?;
+            }
+        }
+
+        if !self.removed.is_empty() {
+            writeln!(f, "\nRemoved:")?;
+            for course in &self.removed {
+                writeln!(f, "  - {course}")?;
+            }
+        }
+
+        if !self.modified.is_empty() {
+            writeln!(f, "\nModified:")?;
+            for course_change in &self.modified {
+                writeln!(f, "  ~ {}", course_change.course)?;
+                for change in &course_change.changes {
+                    writeln!(f, "      {} : {} -> {}", change.field, display_value(&change.before), display_value(&change.after))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// whether the course is listed (within `listed_in`) under a department that failed to download within `failed_in`
+// it's presence within `failed_in` is unknown in that case
+fn listed_in_failed_department(listed_in : &CatalogSnapshot, failed_in : &CatalogSnapshot, course_group_id : &str) -> bool {
+    failed_in.failed_departments.keys().any(|department_code| {
+        listed_in.departments.get(department_code).is_some_and(|courses| {
+            courses.iter().any(|course| sans_io::normalize_course_group_id(&course.course_group_id) == course_group_id)
+        })
+    })
+}
+
+// fields of every course within the snapshot, keyed by course group id
+// the values are rendered as strings, so that the changes can be reported the same way for every field
+type CourseFields = BTreeMap<&'static str, String>;
+
+fn course_fields(snapshot : &CatalogSnapshot) -> BTreeMap<String, CourseFields> {
+    let mut courses : BTreeMap<String, CourseFields> = BTreeMap::new();
+    for course in snapshot.courses() {
+        let course_group_id = sans_io::normalize_course_group_id(&course.course_group_id);
+
+        // cross listed courses appear under every department, the first listing is used
+        courses.entry(course_group_id).or_insert_with(|| course_info_fields(course));
+    }
+
+    for (course_group_id, detail) in &snapshot.course_details {
+        let course_group_id = sans_io::normalize_course_group_id(course_group_id);
+        if let Some(fields) = courses.get_mut(&course_group_id) {
+            fields.extend(course_detail_fields(detail));
+        }
+    }
+
+    courses
+}
+
+fn course_info_fields(course : &CourseInfo) -> CourseFields {
+    let mut departments = course.department.clone();
+    departments.sort();
+
+    BTreeMap::from([
+        ("course_code", course.course_code.clone()),
+        ("course_name", course.course_name.clone()),
+        ("career", course.career.clone()),
+        ("credits", course.credits.clone()),
+        ("components", format_components(std::slice::from_ref(&course.course_components))),
+        ("departments", departments.join(", ")),
+    ])
+}
+
+// the listing only holds the first component of a course, all of them are compared as a separate field
+// since the details may only be present within one of the snapshots
+fn course_detail_fields(detail : &CourseDetail) -> CourseFields {
+    BTreeMap::from([
+        ("long_name", detail.long_name.clone()),
+        ("description", detail.description.clone()),
+        ("detailed_components", format_components(&detail.course_components)),
+        ("typically_offered", detail.typically_offered.clone()),
+        ("consent", detail.consent.clone()),
+        ("status", detail.status.clone()),
+    ])
+}
+
+// i.e. "LEC 3h In Person (35 seats), LAB 2h In Person (20 seats)"
+fn format_components(components : &[CourseComponents]) -> String {
+    components.iter()
+        .map(|component| format!(
+            "{} {}h {} ({} seats)",
+            component.course_type, component.weekly_hours, component.instruction_mode, component.class_size
+        ))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn summary(course_group_id : &str, fields : &CourseFields) -> CourseSummary {
+    CourseSummary {
+        course_group_id : course_group_id.to_owned(),
+        course_code : fields.get("course_code").cloned().unwrap_or_default(),
+        course_name : fields.get("course_name").cloned().unwrap_or_default(),
+    }
+}
+
+// long values (i.e. descriptions) are shortened within the human readable report
+fn display_value(value : &str) -> String {
+    const MAX_CHARACTERS : usize = 80;
+    if value.is_empty() {
+        return String::from("(empty)");
+    }
+
+    if value.chars().count() <= MAX_CHARACTERS {
+        return value.to_owned();
+    }
+
+    let shortened : String = value.chars().take(MAX_CHARACTERS).collect();
+    format!("{shortened}...")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::models::CourseComponents;
+    use crate::snapshot::catalog_snapshot::tests::sample_snapshot;
+
+    #[test]
+    fn test_identical_snapshots_have_no_changes() {
+        let snapshot = sample_snapshot();
+        let diff = snapshot.diff(&snapshot.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No changes\n");
+    }
+
+    #[test]
+    fn test_added_removed_and_modified_courses() {
+        let older = sample_snapshot();
+        let mut newer = sample_snapshot();
+
+        // data structures gains a credit and a new description
+        let courses = newer.departments.get_mut("CSC-CTY").unwrap();
+        courses[0].credits = String::from("4");
+        newer.course_details.get_mut("0455351").unwrap().description = String::from("Updated description");
+
+        // a new course is introduced
+        let mut new_course = courses[0].clone();
+        new_course.course_group_id = String::from("0455352");
+        new_course.course_code = String::from("CSC 21300");
+        new_course.course_name = String::from("Data Structures II");
+        courses.push(new_course);
+
+        let diff = older.diff(&newer);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].course_code, "CSC 21300");
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.modified.len(), 1);
+
+        let fields : Vec<&str> = diff.modified[0].changes.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, vec!["credits", "description"]);
+        assert_eq!(diff.modified[0].changes[0].before, "3");
+        assert_eq!(diff.modified[0].changes[0].after, "4");
+
+        // comparing the other way around swaps the added and removed courses
+        let reversed = newer.diff(&older);
+        assert_eq!(reversed.removed, diff.added);
+
+        let report = diff.to_string();
+        assert!(report.starts_with("1 added, 0 removed, 1 modified"));
+        assert!(report.contains("  + CSC 21300 Data Structures II (0455352)"));
+        assert!(report.contains("      credits : 3 -> 4"));
+    }
+
+    #[test]
+    fn test_details_missing_from_one_snapshot_are_not_compared() {
+        let mut older = sample_snapshot();
+        let lab = CourseComponents { course_type : String::from("LAB"), ..older.departments["CSC-CTY"][0].course_components.clone() };
+        older.course_details.get_mut("0455351").unwrap().course_components.push(lab);
+
+        let mut newer = sample_snapshot();
+        newer.course_details.clear();
+        assert!(older.diff(&newer).is_empty());
+    }
+
+    #[test]
+    fn test_courses_of_failed_departments_are_not_removed() {
+        let older = sample_snapshot();
+        let mut newer = sample_snapshot();
+        newer.departments.remove("CSC-CTY");
+        newer.failed_departments.insert(String::from("CSC-CTY"), String::from("connection reset"));
+
+        assert!(older.diff(&newer).is_empty());
+        assert!(newer.diff(&older).is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+// offline copies of the catalog
+// a snapshot holds everything the catalog knows about at a point in time and can be queried without network access
+
+pub mod catalog_snapshot;
+pub mod diff;
+pub mod offline_catalog;
+
+pub use self::catalog_snapshot::{CatalogSnapshot, SNAPSHOT_FORMAT_VERSION};
+pub use self::diff::{CatalogDiff, CourseChange, CourseSummary, FieldChange};
+pub use self::offline_catalog::OfflineCatalog;
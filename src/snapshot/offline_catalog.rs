@@ -0,0 +1,165 @@
+// query methods of CCNYCourseCatalog, answered from a loaded snapshot instead of the network
+use anyhow::Result;
+use crate::api::department;
+use crate::api::sans_io;
+use crate::models::{CourseDetail, CourseInfo, Term};
+use crate::snapshot::CatalogSnapshot;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Offline counterpart of [`crate::CCNYCourseCatalog`], backed by a [`CatalogSnapshot`].
+///
+/// Cloning an instance shares the snapshot.
+///
+/// Snapshots only store the parsed [`CourseDetail`] rather than the raw responses, so there are no counterparts of
+/// [`crate::CCNYCourseCatalog::get_course_info`] and [`crate::CCNYCourseCatalog::get_course_info_by_group_id`],
+/// use [`OfflineCatalog::get_course_detail`] and [`OfflineCatalog::get_course_detail_by_group_id`] instead.
+///
+/// - Example Usage
+/// ```rust,no_run
+/// use ccny_course_catalog::OfflineCatalog;
+///
+/// fn main() -> anyhow::Result<()> {
+///     let course_instance = OfflineCatalog::load("catalog.json", String::from("computer science"), Some(String::from("data structures")))?;
+///     let list_of_courses = course_instance.get_courses_list()?;
+///     let course_detail = course_instance.get_course_detail()?;
+///     println!("{course_detail:#?}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OfflineCatalog {
+    pub department_name : String,
+    pub course_name : String,
+    snapshot : Arc<CatalogSnapshot>
+}
+
+impl OfflineCatalog {
+
+    /// # constructor function
+    /// - same parameters as [`crate::CCNYCourseCatalog::new`], along with the snapshot to query
+    pub fn new(snapshot : CatalogSnapshot, department_name : String, course_name : Option<String>) -> OfflineCatalog {
+        OfflineCatalog {
+            department_name,
+            course_name : course_name.unwrap_or_default(),
+            snapshot : Arc::new(snapshot)
+        }
+    }
+
+    /// Same as [`OfflineCatalog::new`], reading the snapshot from a file written by [`CatalogSnapshot::save`].
+    pub fn load(path : impl AsRef<Path>, department_name : String, course_name : Option<String>) -> Result<OfflineCatalog> {
+        Ok(OfflineCatalog::new(CatalogSnapshot::load(path)?, department_name, course_name))
+    }
+
+    /// Retrieves list of courses based on name of department.
+    pub fn get_courses_list(&self) -> Result<Vec<CourseInfo>, anyhow::Error> {
+        let department_code = sans_io::resolve_department_code(&self.department_name)
+            .ok_or(anyhow::Error::msg("A department by this name doesn't exist, please refer to the list of departments."))?;
+
+        if let Some(err) = self.snapshot.failed_departments.get(&department_code) {
+            anyhow::bail!("the courses of {department_code} failed to download when the snapshot was taken : {err}");
+        }
+
+        self.snapshot.departments
+            .get(&department_code)
+            .cloned()
+            .ok_or(anyhow::anyhow!("the snapshot doesn't contain the courses of {department_code}"))
+    }
+
+    /// Retrieves information about the current course.
+    pub fn get_course_detail(&self) -> Result<CourseDetail, anyhow::Error> {
+        if self.course_name.is_empty() {
+            return Err(anyhow::Error::msg("course name is empty, please specify a specific course name using .set_course_name('name of course here')"));
+        }
+
+        let courses_by_department = self.get_courses_list()?;
+        let course_group_id = sans_io::find_course_group_id(&courses_by_department, &self.course_name)
+            .ok_or(anyhow::Error::msg("Course Group ID Does not exist for this course"))?;
+        self.get_course_detail_by_group_id(&course_group_id)
+    }
+
+    /// Retrieves information about a course by its course group id (i.e. 0455351).
+    pub fn get_course_detail_by_group_id(&self, course_group_id : &str) -> Result<CourseDetail, anyhow::Error> {
+        let course_group_id = sans_io::normalize_course_group_id(course_group_id);
+
+        if let Some(err) = self.snapshot.failed_course_details.get(&course_group_id) {
+            anyhow::bail!("the details of {course_group_id} failed to download when the snapshot was taken : {err}");
+        }
+
+        self.snapshot.course_details
+            .get(&course_group_id)
+            .cloned()
+            .ok_or(anyhow::anyhow!("the snapshot doesn't contain the details of {course_group_id}"))
+    }
+
+    /// Retrieves the academic terms stored within the snapshot.
+    pub fn get_terms(&self) -> Result<Vec<Term>, anyhow::Error> {
+        if let Some(err) = &self.snapshot.failed_terms {
+            anyhow::bail!("the terms failed to download when the snapshot was taken : {err}");
+        }
+
+        Ok(self.snapshot.terms.clone())
+    }
+
+    /// Returns list of departments available within CUNY City College of New York.
+    pub fn get_department_list(&self) -> Vec<String> {
+        department::get_department_list()
+    }
+
+    /// Setter methods allows modification of department and courses.
+    pub fn set_department_name(&mut self, new_department_name : &str) {
+        self.department_name = String::from(new_department_name);
+    }
+
+    pub fn set_course_name(&mut self, new_course_name : &str) {
+        self.course_name = String::from(new_course_name);
+    }
+
+    /// Returns the snapshot the queries are answered from.
+    pub fn snapshot(&self) -> &CatalogSnapshot {
+        &self.snapshot
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::catalog_snapshot::tests::sample_snapshot;
+
+    #[test]
+    fn test_queries_are_answered_from_snapshot() {
+        let mut course_instance = OfflineCatalog::new(sample_snapshot(), String::from("computer science"), Some(String::from("data structure")));
+        let list_of_courses = course_instance.get_courses_list().unwrap();
+        assert_eq!(list_of_courses[0].course_code, "CSC 21200");
+
+        let course_detail = course_instance.get_course_detail().unwrap();
+        assert_eq!(course_detail.course_group_id, "0455351");
+        assert_eq!(course_instance.get_terms().unwrap()[0].name, "2024 Fall Term");
+
+        // departments that weren't downloaded are reported as errors
+        course_instance.set_department_name("electrical engineering");
+        assert!(course_instance.get_courses_list().is_err());
+
+        let mut snapshot = sample_snapshot();
+        snapshot.departments.remove("CSC-CTY");
+        snapshot.failed_departments.insert(String::from("CSC-CTY"), String::from("connection reset"));
+        let course_instance = OfflineCatalog::new(snapshot, String::from("computer science"), None);
+        assert!(course_instance.get_courses_list().unwrap_err().to_string().contains("connection reset"));
+    }
+
+    #[test]
+    fn test_failed_downloads_are_reported() {
+        let course_instance = OfflineCatalog::new(sample_snapshot(), String::from("computer science"), None);
+        assert_eq!(course_instance.get_course_detail_by_group_id("0455351").unwrap().course_code, "CSC 21200");
+        assert!(course_instance.get_course_detail_by_group_id("0455352").is_err());
+
+        let mut snapshot = sample_snapshot();
+        snapshot.course_details.remove("0455351");
+        snapshot.failed_course_details.insert(String::from("0455351"), String::from("connection reset"));
+        snapshot.failed_terms = Some(String::from("timed out"));
+        let course_instance = OfflineCatalog::new(snapshot, String::from("computer science"), Some(String::from("data structure")));
+        assert!(course_instance.get_course_detail().unwrap_err().to_string().contains("connection reset"));
+        assert!(course_instance.get_terms().unwrap_err().to_string().contains("timed out"));
+    }
+}
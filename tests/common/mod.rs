@@ -0,0 +1,47 @@
+// starts the coursedog-mock stand-in for the integration tests
+// every test starts it's own instance (on a free port), serving the snapshots within tests/fixtures/stand_in
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+// printed by the stand-in once it's listening
+const BASE_URL_PREFIX : &str = "export CCNY_COURSEDOG_BASE_URL=";
+
+// running stand-in, stopped once dropped (including when a test panics)
+pub struct StandIn {
+    pub base_url : String,
+    process : Child,
+    _stdout : BufReader<ChildStdout>,       // kept open, so that the stand-in never writes into a closed pipe
+}
+
+impl StandIn {
+    pub fn spawn() -> StandIn {
+        let snapshot_directory = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/stand_in");
+        let mut process = Command::new(env!("CARGO_BIN_EXE_coursedog-mock"))
+            .args([snapshot_directory, "127.0.0.1:0"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start the stand-in");
+
+        let mut stdout = BufReader::new(process.stdout.take().unwrap());
+        let mut line = String::new();
+        let base_url = loop {
+            line.clear();
+            if stdout.read_line(&mut line).unwrap() == 0 {
+                let _ = process.kill();
+                panic!("the stand-in exited before printing it's address");
+            }
+            if let Some(base_url) = line.trim().strip_prefix(BASE_URL_PREFIX) {
+                break base_url.to_owned();
+            }
+        };
+
+        StandIn { base_url, process, _stdout : stdout }
+    }
+}
+
+impl Drop for StandIn {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
@@ -0,0 +1,66 @@
+// runs the library against the coursedog-mock stand-in, end to end
+// the base url is handed to the library explicitly, since the tests run in parallel and the environment is process wide
+mod common;
+
+use ccny_course_catalog::{CCNYCourseCatalog, CatalogSnapshot, NdjsonCompression, NdjsonWriter, OfflineCatalog};
+use common::StandIn;
+
+#[tokio::test]
+async fn test_library_against_stand_in() {
+    let stand_in = StandIn::spawn();
+    let mut course_instance = CCNYCourseCatalog::new(String::from("computer science"), Some(String::from("data structures")));
+    course_instance.set_base_url(&stand_in.base_url);
+    let list_of_courses = course_instance.get_courses_list().await.unwrap();
+    assert_eq!(list_of_courses.len(), 2);
+    assert_eq!(list_of_courses[0].course_code, "CSC 21200");
+
+    let course_info = course_instance.get_course_info().await.unwrap();
+    assert_eq!(course_info["data"][0]["code"], "CSC 21200");
+
+    let terms = course_instance.get_terms().await.unwrap();
+    assert_eq!(terms[0].name, "2024 Fall Term");
+
+    // looked up by course group id, regardless of the course name
+    let course_detail = course_instance.get_course_detail_by_group_id("0455781").await.unwrap();
+    assert_eq!(course_detail.course_name, "Systems Simulation");
+}
+
+#[tokio::test]
+async fn test_snapshot_against_stand_in() {
+    let stand_in = StandIn::spawn();
+    let snapshot = CatalogSnapshot::fetch_from(&stand_in.base_url, true).await.unwrap();
+    assert_eq!(snapshot.departments["CSC-CTY"].len(), 2);
+    assert_eq!(snapshot.course_details.len(), 3);
+    assert!(snapshot.failed_course_details.is_empty());
+    assert_eq!(snapshot.terms[0].name, "2024 Fall Term");
+
+    let course_instance = OfflineCatalog::new(snapshot, String::from("computer science"), Some(String::from("data structures")));
+    assert_eq!(course_instance.get_course_detail().unwrap().course_code, "CSC 21200");
+}
+
+#[tokio::test]
+async fn test_ndjson_against_stand_in() {
+    let stand_in = StandIn::spawn();
+    let mut writer = NdjsonWriter::new(Vec::new(), NdjsonCompression::None);
+    writer.set_base_url(&stand_in.base_url);
+    assert_eq!(writer.write_all_courses().await.unwrap(), 3);
+    assert_eq!(writer.write_all_course_details().await.unwrap(), 3);
+
+    let output = String::from_utf8(writer.finish().await.unwrap()).unwrap();
+    assert_eq!(output.lines().count(), 6);
+    assert!(output.lines().all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
+}
+
+#[test]
+fn test_blocking_library_against_stand_in() {
+    let stand_in = StandIn::spawn();
+    let mut course_instance = ccny_course_catalog::blocking::CCNYCourseCatalog::new(String::from("electrical engineering"), None).unwrap();
+    course_instance.set_base_url(&stand_in.base_url);
+    let list_of_courses = course_instance.get_courses_list().unwrap();
+    assert_eq!(list_of_courses.len(), 1);
+    assert_eq!(list_of_courses[0].course_name, "Switching Systems");
+
+    course_instance.set_course_name("switching");
+    let course_info = course_instance.get_course_info().unwrap();
+    assert_eq!(course_info["data"][0]["courseGroupId"], "0460001");
+}